@@ -4,6 +4,8 @@
 use crate::board::Board;
 use crate::game::{Player, GameState, CellState};
 use rand::Rng;
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum AIStrategy {
@@ -20,53 +22,114 @@ pub enum Heuristic {
     ConversionPotential,
     CascadePotential,
     SafeMobility,
+    Mobility,
+    Influence,
+    CenterControl,
 }
 
+/// What one AI decision cost: the deepest search that finished, the positions it visited and the
+/// time taken. Random moves report depth 0 and no nodes.
+#[derive(Debug, Clone, Copy)]
+pub struct SearchStats {
+    pub chosen: (usize, usize),
+    pub depth_reached: u32,
+    pub nodes: u64,
+    pub elapsed: Duration,
+}
 
-/// The main entry point for getting the AI's move.
-pub fn get_ai_move(board: &Board, strategy: AIStrategy, heuristics: &[Heuristic], depth: u32) -> (usize, usize) {
-    match strategy {
+/// The main entry point for getting the AI's move, with what it cost to find. With a time limit,
+/// alpha-beta deepens one ply at a time and falls back to the deepest search that finished before
+/// the deadline.
+pub fn get_ai_move(board: &Board, strategy: AIStrategy, heuristics: &[Heuristic], depth: u32, time_limit_ms: Option<u64>) -> SearchStats {
+    let start = Instant::now();
+    let (chosen, depth_reached, nodes) = match strategy {
         AIStrategy::Random => {
-            let mut rng = rand::thread_rng();
+            let mut rng = rand::rng();
             loop {
-                let row = rng.gen_range(0..board.height as usize);
-                let col = rng.gen_range(0..board.width as usize);
+                let row = rng.random_range(0..board.height as usize);
+                let col = rng.random_range(0..board.width as usize);
                 let mut temp_board = board.clone();
                 if temp_board.make_move(row, col).is_ok() {
-                    return (row, col);
+                    break ((row, col), 0, 0);
                 }
             }
         }
         AIStrategy::AlphaBeta => {
-            find_best_move_alphabeta(board, heuristics, depth)
+            find_best_move_alphabeta(board, heuristics, depth, time_limit_ms)
+        }
+    };
+    SearchStats { chosen, depth_reached, nodes, elapsed: start.elapsed() }
+}
+
+/// Iterative deepening over depths 1..=depth. This is the top-level "manager" function. Returns the
+/// move, the deepest depth that finished and the positions visited across all depths.
+fn find_best_move_alphabeta(board: &Board, heuristics: &[Heuristic], depth: u32, time_limit_ms: Option<u64>) -> ((usize, usize), u32, u64) {
+    let possible_moves = board.get_all_valid_moves();
+    if possible_moves.is_empty() {
+        return ((0, 0), 0, 0);
+    }
+
+    let mut search = Search {
+        heuristics,
+        // The player whose turn it is at the root of the search. This is our consistent Point of View.
+        player_for_pov: board.current_turn,
+        deadline: time_limit_ms.map(|ms| Instant::now() + Duration::from_millis(ms)),
+        nodes: 0,
+    };
+    let mut best_move = possible_moves[0];
+    let mut depth_reached = 0;
+
+    // Depth 0 still searches one ply, scoring each root move by the board it leaves.
+    for d in 1..=depth.max(1) {
+        if search.timed_out() {
+            break;
+        }
+        match find_best_move_at_depth(board, &possible_moves, d, &mut search) {
+            Some(found_move) => {
+                best_move = found_move;
+                depth_reached = d;
+            }
+            // The deeper search ran out of time; keep the move from the last completed depth.
+            None => break,
         }
     }
+    (best_move, depth_reached, search.nodes)
 }
 
-/// Finds the best move using the alpha-beta algorithm. This is the top-level "manager" function.
-fn find_best_move_alphabeta(board: &Board, heuristics: &[Heuristic], depth: u32) -> (usize, usize) {
-    let mut best_move: (usize, usize) = (0, 0);
+/// What every node of one search shares: how positions are scored, and from whose point of view,
+/// the deadline and the count of positions searched so far.
+struct Search<'a> {
+    heuristics: &'a [Heuristic],
+    player_for_pov: Player,
+    deadline: Option<Instant>,
+    nodes: u64,
+}
+
+impl Search<'_> {
+    fn timed_out(&self) -> bool {
+        self.deadline.is_some_and(|deadline| Instant::now() >= deadline)
+    }
+
+    fn evaluate(&self, board: &Board) -> f64 {
+        evaluate_board(board, self.heuristics, self.player_for_pov)
+    }
+}
+
+/// One full alpha-beta search to `depth`, or None if the deadline passed before it finished. Every
+/// position searched is counted in `search.nodes`.
+fn find_best_move_at_depth(board: &Board, possible_moves: &[(usize, usize)], depth: u32, search: &mut Search) -> Option<(usize, usize)> {
+    let mut best_move = possible_moves[0];
     let mut best_score = f64::NEG_INFINITY; 
 
     let alpha = f64::NEG_INFINITY;
     let beta = f64::INFINITY;
-    
-    let possible_moves = board.get_all_valid_moves();
-    if possible_moves.is_empty() {
-        return (0, 0);
-    }
-
-    best_move = possible_moves[0];
-    
-    // The player whose turn it is at the root of the search. This is our consistent Point of View.
-    let player_pov = board.current_turn;
 
-    for a_move in possible_moves {
+    for &a_move in possible_moves {
         let mut temp_board = board.clone();
         temp_board.make_move(a_move.0, a_move.1).unwrap();
 
         // We are the maximizing player, so the next turn is the minimizing player (is_maximizing_player = false)
-        let score = alphabeta(&temp_board, depth - 1, alpha, beta, false, heuristics, player_pov);
+        let score = alphabeta(&temp_board, depth - 1, alpha, beta, false, search)?;
 
         // We want the move that results in the HIGHEST score from our Point of View.
         if score > best_score {
@@ -74,18 +137,23 @@ fn find_best_move_alphabeta(board: &Board, heuristics: &[Heuristic], depth: u32)
             best_move = a_move;
         }
     }
-    return best_move;
+    Some(best_move)
 }
 
-/// The core recursive helper function for the alpha-beta algorithm.
-fn alphabeta(board: &Board, depth: u32, mut alpha: f64, mut beta: f64, is_maximizing_player: bool, heuristics: &[Heuristic], player_for_pov: Player) -> f64 {
+/// The core recursive helper function for the alpha-beta algorithm. None means the deadline passed.
+fn alphabeta(board: &Board, depth: u32, mut alpha: f64, mut beta: f64, is_maximizing_player: bool, search: &mut Search) -> Option<f64> {
+    if search.timed_out() {
+        return None;
+    }
+    search.nodes += 1;
+
     if depth == 0 || board.game_state != GameState::Ongoing {
-        return evaluate_board(&board, heuristics, player_for_pov);
+        return Some(search.evaluate(board));
     }
 
     let possible_moves = board.get_all_valid_moves();
     if possible_moves.is_empty() {
-        return evaluate_board(&board, heuristics, player_for_pov);
+        return Some(search.evaluate(board));
     }
 
     if is_maximizing_player {
@@ -94,7 +162,7 @@ fn alphabeta(board: &Board, depth: u32, mut alpha: f64, mut beta: f64, is_maximi
             let mut child_board = board.clone();
             child_board.make_move(a_move.0, a_move.1).unwrap();
 
-            let eval = alphabeta(&child_board, depth - 1, alpha, beta, false, heuristics, player_for_pov);
+            let eval = alphabeta(&child_board, depth - 1, alpha, beta, false, search)?;
             max_eval = max_eval.max(eval);
             alpha = alpha.max(eval);
 
@@ -102,21 +170,21 @@ fn alphabeta(board: &Board, depth: u32, mut alpha: f64, mut beta: f64, is_maximi
                 break;
             }
          }
-         return max_eval;
+         Some(max_eval)
     }
     else {
         let mut min_eval = f64::INFINITY;
         for a_move in possible_moves {
             let mut child_board = board.clone();
             child_board.make_move(a_move.0, a_move.1).unwrap();
-            let eval = alphabeta(&child_board, depth - 1, alpha, beta, true, heuristics, player_for_pov);
+            let eval = alphabeta(&child_board, depth - 1, alpha, beta, true, search)?;
             min_eval = min_eval.min(eval);
             beta = beta.min(eval);
             if beta <= alpha {
                 break;
             }
         }
-        return min_eval;
+        Some(min_eval)
     }
 }
 
@@ -128,6 +196,7 @@ fn evaluate_board(board: &Board, heuristics: &[Heuristic], player_for_pov: Playe
     let opponent = if player == Player::Red { Player::Blue } else { Player::Red };
 
     // Handle game-ending states with the highest/lowest possible scores
+    if board.game_state == GameState::Draw { return 0.0; }
     if let GameState::Won { winner } = board.game_state {
         if winner == player { return f64::INFINITY; }
         if winner == opponent { return f64::NEG_INFINITY; }
@@ -230,12 +299,7 @@ fn evaluate_board(board: &Board, heuristics: &[Heuristic], player_for_pov: Playe
                     let opponent_replies = opponent_board_view.get_all_valid_moves();
 
                     for opp_reply in &opponent_replies {
-                        let target_cell = board_after_my_move.cells[opp_reply.0][opp_reply.1];
-                        let would_explode = match target_cell.state {
-                            CellState::Occupied { orbs, .. } => orbs + 1 == target_cell.critical_mass,
-                            CellState::Empty => 1 == target_cell.critical_mass,
-                        };
-                        if would_explode {
+                        if board_after_my_move.cells[opp_reply.0][opp_reply.1].explodes_on_next_orb() {
                             is_move_safe = false;
                             break;
                         }
@@ -277,8 +341,103 @@ fn evaluate_board(board: &Board, heuristics: &[Heuristic], player_for_pov: Playe
                 }
                 cascade_score
             }
+            Heuristic::Mobility => {
+                // Moves each side would have if it were their turn.
+                let mut my_view = board.clone();
+                my_view.current_turn = player;
+                let mut opponent_view = board.clone();
+                opponent_view.current_turn = opponent;
+                my_view.get_all_valid_moves().len() as f64 - opponent_view.get_all_valid_moves().len() as f64
+            }
+            Heuristic::Influence => {
+                // Each empty cell goes to whichever side's orbs it is fewer steps from; ties go to no one.
+                let mine = steps_from(board, player);
+                let theirs = steps_from(board, opponent);
+                let mut influence_score = 0.0;
+                for (index, cell) in board.cells.iter().flatten().enumerate() {
+                    if cell.state != CellState::Empty { continue; }
+                    match (mine[index], theirs[index]) {
+                        (Some(m), Some(t)) if m < t => influence_score += 1.0,
+                        (Some(m), Some(t)) if m > t => influence_score -= 1.0,
+                        (Some(_), None) => influence_score += 1.0,
+                        (None, Some(_)) => influence_score -= 1.0,
+                        _ => {}
+                    }
+                }
+                influence_score
+            }
+            Heuristic::CenterControl => {
+                // Owned cells count more the closer (Manhattan) they sit to the middle of the board.
+                let center_r = (board.height as f64 - 1.0) / 2.0;
+                let center_c = (board.width as f64 - 1.0) / 2.0;
+                let mut center_score = 0.0;
+                for r in 0..board.height as usize {
+                    for c in 0..board.width as usize {
+                        if let CellState::Occupied { player: cell_player, .. } = board.cells[r][c].state {
+                            let distance = (r as f64 - center_r).abs() + (c as f64 - center_c).abs();
+                            let value = 1.0 / (1.0 + distance);
+                            if cell_player == player {
+                                center_score += value;
+                            } else {
+                                center_score -= value;
+                            }
+                        }
+                    }
+                }
+                center_score
+            }
         }
     }
     
     total_score
 }
+
+/// Breadth-first distance from the nearest of `player`'s cells to every cell, indexed row-major.
+/// None where `player` has no cells to start from.
+fn steps_from(board: &Board, player: Player) -> Vec<Option<u32>> {
+    let (width, height) = (board.width as usize, board.height as usize);
+    let neighbors_diff: [(isize, isize); 4] = [(-1, 0), (1, 0), (0, -1), (0, 1)];
+    let mut steps = vec![None; width * height];
+    let mut frontier = VecDeque::new();
+    for (index, cell) in board.cells.iter().flatten().enumerate() {
+        if matches!(cell.state, CellState::Occupied { player: owner, .. } if owner == player) {
+            steps[index] = Some(0);
+            frontier.push_back((index / width, index % width));
+        }
+    }
+    while let Some((r, c)) = frontier.pop_front() {
+        let next = steps[r * width + c].map(|s: u32| s + 1);
+        for (dr, dc) in &neighbors_diff {
+            let nr = r as isize + dr;
+            let nc = c as isize + dc;
+            if nr >= 0 && nr < height as isize && nc >= 0 && nc < width as isize {
+                let index = nr as usize * width + nc as usize;
+                if steps[index].is_none() {
+                    steps[index] = next;
+                    frontier.push_back((nr as usize, nc as usize));
+                }
+            }
+        }
+    }
+    steps
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn depth_zero_still_picks_a_legal_move() {
+        let log = std::env::temp_dir().join("backend_ai_test_depth_zero.txt");
+        let mut board = Board::new(5, 5, Player::Red, log.to_string_lossy().into_owned());
+        for (row, col) in [(0, 0), (4, 4), (2, 2), (2, 3), (0, 0), (4, 4), (1, 2), (3, 3), (2, 2), (4, 3)] {
+            board.make_move(row, col).unwrap();
+        }
+        assert!(!board.is_over());
+
+        let stats = get_ai_move(&board, AIStrategy::AlphaBeta, &[Heuristic::OrbDifference], 0, None);
+        assert!(board.get_all_valid_moves().contains(&stats.chosen));
+        assert_eq!(stats.depth_reached, 1);
+        assert!(stats.nodes > 0);
+    }
+}
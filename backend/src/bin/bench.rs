@@ -0,0 +1,96 @@
+// Plays AI-vs-AI games and reports how two configurations compare, so a heuristic change can be
+// checked against the old one instead of judged by feel.
+//
+//     cargo run --release --bin bench -- [games] [WIDTHxHEIGHT] [config A] [config B]
+//
+// A config is `random` or `alphabeta[:depth[:Heuristic,Heuristic,...]]`, e.g.
+// `alphabeta:3:OrbDifference,CascadePotential`. The two configs swap colours every game so neither
+// keeps the first move, and each pair of games starts from the same seeded random opening so a
+// rerun replays the same openings.
+
+use std::env;
+use std::process;
+
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+use backend::selfplay::{AIConfig, Tally, parse_config, parse_size, play_game};
+
+const DEFAULT_GAMES: u32 = 10;
+const DEFAULT_SIZE: (u32, u32) = (6, 9);
+const LOG_FILE: &str = "bench_log.txt";
+const BENCH_SEED: u64 = 0xBE4C_0001;
+
+/// Reads `[games] [WIDTHxHEIGHT] [config A] [config B]`; anything left out keeps its default.
+fn parse_args(args: &[String]) -> Result<(u32, (u32, u32), AIConfig, AIConfig), String> {
+    if args.len() > 4 {
+        return Err(format!("Expected at most 4 arguments, got {}", args.len()));
+    }
+    let games = match args.first() {
+        Some(games) => games.parse().map_err(|_| format!("Invalid game count '{}'", games))?,
+        None => DEFAULT_GAMES,
+    };
+    let size = args.get(1).map_or(Ok(DEFAULT_SIZE), |size| parse_size(size))?;
+    let first = parse_config(args.get(2).map_or("alphabeta", String::as_str))?;
+    let second = parse_config(args.get(3).map_or("random", String::as_str))?;
+    Ok((games, size, first, second))
+}
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let (games, size, first, second) = match parse_args(&args) {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            eprintln!("{}", e);
+            eprintln!("Usage: bench [games] [WIDTHxHEIGHT] [config A] [config B]");
+            eprintln!("  config: random | alphabeta[:depth[:Heuristic,Heuristic,...]]");
+            process::exit(2);
+        }
+    };
+
+    let configs = [&first, &second];
+    let mut tallies = [Tally::default(), Tally::default()];
+    let mut draws = 0;
+    for game in 0..games {
+        // Config A plays Red in even games and Blue in odd ones, from the same opening both times.
+        let swap = game % 2 == 1;
+        let mut rng = StdRng::seed_from_u64(BENCH_SEED + u64::from(game / 2));
+        let seats = if swap { [configs[1], configs[0]] } else { configs };
+        let mut seat_tallies = [Tally::default(), Tally::default()];
+        let winner = play_game(seats, &mut seat_tallies, size, LOG_FILE, &mut rng);
+        if swap { seat_tallies.swap(0, 1); }
+
+        let result = match winner.map(|seat| if swap { 1 - seat } else { seat }) {
+            Some(index) => {
+                tallies[index].wins += 1;
+                format!("{} wins", configs[index].label)
+            }
+            None => {
+                draws += 1;
+                "draw".to_string()
+            }
+        };
+        println!("Game {}/{}: {}", game + 1, games, result);
+        for (tally, seat_tally) in tallies.iter_mut().zip(seat_tallies) {
+            tally.moves += seat_tally.moves;
+            tally.nodes += seat_tally.nodes;
+            tally.depth += seat_tally.depth;
+            tally.time += seat_tally.time;
+        }
+    }
+
+    println!("\n{} games on {}x{}, {} drawn", games, size.0, size.1, draws);
+    for (config, tally) in configs.iter().zip(&tallies) {
+        let moves = tally.moves.max(1);
+        println!(
+            "{}: won {} ({:.1}%), {:.0} nodes, depth {:.1} and {:.1} ms per move",
+            config.label,
+            tally.wins,
+            100.0 * tally.wins as f64 / games.max(1) as f64,
+            tally.nodes as f64 / moves as f64,
+            tally.depth as f64 / moves as f64,
+            tally.time.as_secs_f64() * 1000.0 / moves as f64,
+        );
+    }
+    let _ = std::fs::remove_file(LOG_FILE);
+}
@@ -0,0 +1,106 @@
+// Tunes the desktop app's heuristic weights over self-play: coordinate ascent from
+// chain-reaction-core's default weights, keeping each change that wins more games against an AI
+// still on those defaults. The result is written as `HeuristicWeights` JSON, ready to drop into
+// `AIConfigData.weights`.
+//
+//     cargo run --release --bin tune -- [games] [WIDTHxHEIGHT] [depth] [iterations] [output.json]
+//
+// Both sides use every heuristic at a fixed depth, with a time limit too generous to cut a search
+// short, and game i of every comparison opens from seed TUNE_SEED + i, so a run with the same
+// arguments always lands on the same weights.
+
+use std::env;
+use std::fs;
+use std::process;
+
+use backend::selfplay::parse_size;
+use chain_reaction_core::ai::{AIStrategy, EvalOptions, Heuristic};
+use chain_reaction_core::selfplay::{self, AIPlayerConfig, OPTIMIZER_MAX_GAMES_PER_EVAL, OPTIMIZER_MAX_ITERATIONS, SelfPlayParams};
+
+const DEFAULT_GAMES: u32 = 10;
+const DEFAULT_SIZE: (u32, u32) = (5, 5);
+const DEFAULT_DEPTH: u32 = 2;
+// One pass over the heuristics.
+const DEFAULT_ITERATIONS: u32 = Heuristic::ALL.len() as u32;
+const DEFAULT_OUTPUT: &str = "tuned_weights.json";
+const TUNE_SEED: u64 = 0x5EED_C4A1;
+// Searches stop at the depth, never at the clock, so results don't depend on the machine.
+const TIME_LIMIT_MS: u64 = 600_000;
+// The search is deterministic, so each game opens with a few random moves to keep games apart.
+const RANDOM_OPENING_PLIES: u32 = 2;
+
+struct TuneArgs {
+    games: u32,
+    size: (u32, u32),
+    depth: u32,
+    iterations: u32,
+    output: String,
+}
+
+/// Reads `[games] [WIDTHxHEIGHT] [depth] [iterations] [output.json]`; anything left out keeps its default.
+fn parse_args(args: &[String]) -> Result<TuneArgs, String> {
+    if args.len() > 5 {
+        return Err(format!("Expected at most 5 arguments, got {}", args.len()));
+    }
+    let games = match args.first() {
+        Some(games) => games.parse().map_err(|_| format!("Invalid game count '{}'", games))?,
+        None => DEFAULT_GAMES,
+    };
+    if !(1..=OPTIMIZER_MAX_GAMES_PER_EVAL).contains(&games) {
+        return Err(format!("Game count must be between 1 and {}, got {}", OPTIMIZER_MAX_GAMES_PER_EVAL, games));
+    }
+    let size = args.get(1).map_or(Ok(DEFAULT_SIZE), |size| parse_size(size))?;
+    let depth = match args.get(2) {
+        Some(depth) => depth.parse().map_err(|_| format!("Invalid depth '{}'", depth))?,
+        None => DEFAULT_DEPTH,
+    };
+    let iterations = match args.get(3) {
+        Some(iterations) => iterations.parse().map_err(|_| format!("Invalid iteration count '{}'", iterations))?,
+        None => DEFAULT_ITERATIONS,
+    };
+    if iterations > OPTIMIZER_MAX_ITERATIONS {
+        return Err(format!("At most {} iterations are allowed, got {}", OPTIMIZER_MAX_ITERATIONS, iterations));
+    }
+    let output = args.get(4).cloned().unwrap_or_else(|| DEFAULT_OUTPUT.to_string());
+    Ok(TuneArgs { games, size, depth, iterations, output })
+}
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let args = match parse_args(&args) {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            eprintln!("{}", e);
+            eprintln!("Usage: tune [games] [WIDTHxHEIGHT] [depth] [iterations] [output.json]");
+            process::exit(2);
+        }
+    };
+
+    let defaults = AIPlayerConfig {
+        strategy: AIStrategy::AlphaBeta,
+        heuristics: Heuristic::ALL.to_vec(),
+        eval_options: EvalOptions::default(),
+        depth: args.depth,
+        time_limit_ms: TIME_LIMIT_MS,
+    };
+    let params = SelfPlayParams {
+        width: args.size.0,
+        height: args.size.1,
+        games: args.games,
+        seed: TUNE_SEED,
+        random_opening_plies: RANDOM_OPENING_PLIES,
+    };
+    println!(
+        "Tuning for {} iterations, {} games per candidate on {}x{} at depth {}",
+        args.iterations, args.games, args.size.0, args.size.1, args.depth
+    );
+    let weights = selfplay::optimize_weights(&defaults, &defaults, &params, args.iterations);
+
+    let json = serde_json::to_string_pretty(&weights).expect("weights are plain numbers");
+    println!("\n{}", json);
+    if let Err(e) = fs::write(&args.output, format!("{}\n", json)) {
+        eprintln!("Could not write {}: {}", args.output, e);
+        process::exit(1);
+    }
+    println!("Wrote {}", args.output);
+}
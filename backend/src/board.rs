@@ -1,8 +1,11 @@
 // This file contains the Board struct and its core game logic implementation.
 // It uses items from the `game` module. The AI logic is now separate.
 
+use std::collections::hash_map::DefaultHasher;
 use std::collections::{HashMap, VecDeque};
+use std::fmt;
 use std::fs::{File, OpenOptions};
+use std::hash::{Hash, Hasher};
 use std::io::Write;
 use crate::game::{Player, Cell, GameState, CellState};
 
@@ -16,19 +19,29 @@ pub struct Board {
     pub current_turn: Player,
     pub game_state: GameState,
     pub total_moves: u32,
+    // How often each position (cells plus player to move) has come up, for draw by repetition.
+    position_counts: HashMap<u64, u32>,
     log_filename: String,
 }
 
+// A position repeated this many times is a draw.
+const REPETITION_LIMIT: u32 = 3;
+// Explosions allowed per (cell x critical mass) before a cascade is treated as non-terminating.
+const CHAIN_REACTION_CAP_FACTOR: usize = 4;
+
 impl Board {
     pub fn new(width: u32, height: u32, first_turn: Player, log_filename: String) -> Self {
         let mut cells = Vec::with_capacity(height as usize);
         for r in 0..height {
             let mut row = Vec::with_capacity(width as usize);
             for c in 0..width {
-                let mut neighbours = 4;
-                if r == 0 || r == height - 1 { neighbours -= 1; }
-                if c == 0 || c == width - 1 { neighbours -= 1; }
-                row.push(Cell::new(neighbours));
+                // Count the neighbours actually on the board, so 1-wide and 1-tall boards come out right.
+                // A lone 1x1 cell still needs a critical mass of at least 1.
+                let offsets: [(i64, i64); 4] = [(-1, 0), (1, 0), (0, -1), (0, 1)];
+                let neighbours = offsets.iter()
+                    .filter(|(dr, dc)| (0..height as i64).contains(&(r as i64 + dr)) && (0..width as i64).contains(&(c as i64 + dc)))
+                    .count() as u32;
+                row.push(Cell::new(neighbours.max(1)));
             }
             cells.push(row);
         }
@@ -47,13 +60,27 @@ impl Board {
             current_turn: first_turn,
             game_state: GameState::Ongoing,
             total_moves: 0,
+            position_counts: HashMap::new(),
             log_filename,
         }
     }
 
-    pub fn make_move(&mut self, row: usize, col: usize) -> Result<(), &'static str> {
-        if self.game_state != GameState::Ongoing {
-            return Err("The game has already been won.");
+    // The player who has won, or None while the game is ongoing or after a draw.
+    pub fn winner(&self) -> Option<Player> {
+        match self.game_state {
+            GameState::Won { winner } => Some(winner),
+            GameState::Ongoing | GameState::Draw => None,
+        }
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.game_state != GameState::Ongoing
+    }
+
+    // Returns the game state after the move so callers don't have to re-read `game_state`.
+    pub fn make_move(&mut self, row: usize, col: usize) -> Result<GameState, &'static str> {
+        if self.is_over() {
+            return Err("The game is already over.");
         }
         if row >= self.height as usize || col >= self.width as usize {
             return Err("Move is out of bounds.");
@@ -75,10 +102,11 @@ impl Board {
                 Player::Red => Player::Blue,
                 Player::Blue => Player::Red,
             };
+            self.record_position();
         }
         
         self.total_moves += 1;
-        Ok(())
+        Ok(self.game_state)
     }
 
     pub fn log_move(&self, player: Player, row: usize, col: usize) {
@@ -93,13 +121,29 @@ impl Board {
 
     fn handle_chain_reaction(&mut self, start_row: usize, start_col: usize) {
         let mut exploding_cells: VecDeque<(usize, usize)> = VecDeque::new();
+        let mut explosions = 0;
+        // A real game is decided long before this many explosions; hitting it means the cascade
+        // would never settle (e.g. one colour owns the whole board before both have moved), so it is
+        // cut off rather than hanging the caller.
+        let max_critical_mass = self.cells.iter().flatten().map(|cell| cell.critical_mass).max().unwrap_or(1) as usize;
+        let explosion_cap = self.width as usize * self.height as usize * max_critical_mass * CHAIN_REACTION_CAP_FACTOR;
         if self.cells[start_row][start_col].get_explosion_data().is_some() {
             exploding_cells.push_back((start_row, start_col));
             self.cells[start_row][start_col].is_queued = true;
         }
 
         while let Some((r, c)) = exploding_cells.pop_front() {
+            if explosions >= explosion_cap {
+                eprintln!("Warning: chain reaction stopped after {} explosions without settling", explosions);
+                self.cells[r][c].is_queued = false;
+                for (qr, qc) in exploding_cells.drain(..) {
+                    self.cells[qr][qc].is_queued = false;
+                }
+                break;
+            }
+
             if let Some((exploding_player, current_orbs)) = self.cells[r][c].get_explosion_data() {
+                explosions += 1;
                 let crit_mass = self.cells[r][c].critical_mass;
                 let remaining_orbs = current_orbs.saturating_sub(crit_mass);
                 
@@ -149,6 +193,12 @@ impl Board {
                     exploding_cells.push_back((r, c));
                     cell_after_explosion.is_queued = true;
                 }
+
+                // Once a player is wiped out the game is over and the rest of the cascade can't matter.
+                self.update_game_state();
+                if self.game_state != GameState::Ongoing {
+                    break;
+                }
             }
         }
     }
@@ -166,22 +216,27 @@ impl Board {
         }
     }
 
-    pub fn print(&self) {
-        println!("--- Turn: {:?} | Game: {:?} | Orbs: R-{} B-{} ---", self.current_turn, self.game_state, self.orb_counts[&Player::Red], self.orb_counts[&Player::Blue]);
-        for row in &self.cells {
-            for cell in row {
-                match cell.state {
-                    CellState::Empty => print!("[ ] "),
-                    CellState::Occupied { player, orbs } => {
-                        let symbol = if player == Player::Red { 'R' } else { 'B' };
-                        print!("[{}{}] ", orbs, symbol);
-                    }
-                }
-            }
-            println!();
+    fn position_key(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        for cell in self.cells.iter().flatten() {
+            cell.state.hash(&mut hasher);
+        }
+        self.current_turn.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    fn record_position(&mut self) {
+        let count = self.position_counts.entry(self.position_key()).or_insert(0);
+        *count += 1;
+        if *count >= REPETITION_LIMIT {
+            self.game_state = GameState::Draw;
         }
     }
 
+    pub fn print(&self) {
+        print!("{}", self);
+    }
+
     // These two methods remain on Board because they are direct queries about the board's state.
     // The AI module will call them.
     pub fn get_all_valid_moves(&self) -> Vec<(usize, usize)> {
@@ -203,3 +258,78 @@ impl Board {
         valid_moves
     }
 }
+
+// The turn, game state and orb totals, then the grid as `[2R] [ ]` rows.
+impl fmt::Display for Board {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "--- Turn: {:?} | Game: {:?} | Orbs: R-{} B-{} ---", self.current_turn, self.game_state, self.orb_counts[&Player::Red], self.orb_counts[&Player::Blue])?;
+        for row in &self.cells {
+            for cell in row {
+                match cell.state {
+                    CellState::Empty => write!(f, "[ ] ")?,
+                    CellState::Occupied { player, orbs } => {
+                        let symbol = if player == Player::Red { 'R' } else { 'B' };
+                        write!(f, "[{}{}] ", orbs, symbol)?;
+                    }
+                }
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_board(width: u32, height: u32) -> Board {
+        let log = std::env::temp_dir().join(format!("backend_board_test_{}x{}.txt", width, height));
+        Board::new(width, height, Player::Red, log.to_string_lossy().into_owned())
+    }
+
+    #[test]
+    fn saturated_two_by_two_cascade_is_cut_off() {
+        // Every cell is one orb short of exploding and Blue hasn't moved, so no one can win and the
+        // cascade would go round the board forever.
+        let mut board = test_board(2, 2);
+        for cell in board.cells.iter_mut().flatten() {
+            cell.state = CellState::Occupied { player: Player::Red, orbs: 1 };
+        }
+        board.orb_counts.insert(Player::Red, 4);
+
+        assert_eq!(board.make_move(0, 0), Ok(GameState::Ongoing));
+        assert!(board.cells.iter().flatten().all(|cell| !cell.is_queued));
+        let orbs: u32 = board.cells.iter().flatten()
+            .map(|cell| match cell.state { CellState::Occupied { orbs, .. } => orbs, CellState::Empty => 0 })
+            .sum();
+        assert_eq!(orbs, 5);
+        assert_eq!(board.orb_counts[&Player::Red], 5);
+    }
+
+    fn critical_masses(board: &Board) -> Vec<Vec<u32>> {
+        board.cells.iter().map(|row| row.iter().map(|cell| cell.critical_mass).collect()).collect()
+    }
+
+    #[test]
+    fn degenerate_boards_get_their_real_neighbour_counts() {
+        assert_eq!(critical_masses(&test_board(1, 1)), vec![vec![1]]);
+        // One column of five rows, then one row of five columns.
+        assert_eq!(critical_masses(&test_board(1, 5)), vec![vec![1], vec![2], vec![2], vec![2], vec![1]]);
+        assert_eq!(critical_masses(&test_board(5, 1)), vec![vec![1, 2, 2, 2, 1]]);
+    }
+
+    #[test]
+    fn make_move_returns_the_state_it_leaves() {
+        let mut board = test_board(3, 3);
+        let mut moves = 0;
+        while !board.is_over() && moves < 500 {
+            let (row, col) = *board.get_all_valid_moves().last().unwrap();
+            let state = board.make_move(row, col).unwrap();
+            assert_eq!(state, board.game_state);
+            moves += 1;
+        }
+        assert!(board.winner().is_some(), "no winner after {} moves", moves);
+        assert!(board.make_move(0, 0).is_err());
+    }
+}
@@ -20,78 +20,19 @@ pub enum Heuristic {
     ConversionPotential,
     CascadePotential,
     SafeMobility,
+    Mobility,
+    Influence,
+    CenterControl,
 }
 
 
 // --- Enums and Structs ---
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-pub enum Player {
-    Red,
-    Blue,
-}
-
-#[derive(Debug, Clone, Copy, PartialEq)]
-pub enum CellState {
-    Empty,
-    Occupied { player: Player, orbs: u32 },
-}
-
-#[derive(Debug, Clone, Copy, PartialEq)]
-pub enum GameState {
-    Ongoing,
-    Won { winner: Player },
-}
-
-#[derive(Debug, Clone, Copy)]
-pub struct Cell {
-    state: CellState,
-    critical_mass: u32,
-    is_queued: bool,
-}
-
-impl Cell {
-    fn new(critical_mass: u32) -> Self {
-        Cell {
-            state: CellState::Empty,
-            critical_mass,
-            is_queued: false,
-        }
-    }
-
-    fn add_orb(&mut self, player: Player) -> bool {
-        match self.state {
-            CellState::Empty => {
-                self.state = CellState::Occupied { player, orbs: 1 };
-                true
-            }
-            CellState::Occupied { player: p, orbs } => {
-                if p == player {
-                    self.state = CellState::Occupied { player, orbs: orbs + 1 };
-                    true
-                } else {
-                    false
-                }
-            }
-        }
-    }
-    
-    fn get_explosion_data(&self) -> Option<(Player, u32)> {
-        if let CellState::Occupied { player, orbs } = self.state {
-            if orbs >= self.critical_mass {
-                return Some((player, orbs));
-            }
-        }
-        None
-    }
-
-    fn take_over(&mut self, player: Player) {
-        let orbs = match self.state {
-            CellState::Occupied { orbs, .. } => orbs,
-            CellState::Empty => 0,
-        };
-        self.state = CellState::Occupied { player, orbs: orbs + 1 };
-    }
-}
+// The cell rules (including the explodes-on-next-orb predicate SafeMobility relies on) are shared
+// with the modular backend rather than copied here.
+#[path = "game.rs"]
+#[allow(dead_code)] // This game has no draw rule.
+mod game;
+use game::{Cell, CellState, GameState, Player};
 
 // --- Board Struct ---
 #[derive(Clone)]
@@ -313,7 +254,7 @@ impl Board {
     fn find_best_move_alphabeta(&self, heuristics: &[Heuristic], depth: u32) -> (usize, usize) {
 
         let mut best_move: (usize, usize) = (0, 0);
-        let mut best_score = f64::INFINITY;
+        let mut best_score = f64::NEG_INFINITY;
         let alpha = f64::NEG_INFINITY;
         let beta = f64::INFINITY;
         let possible_moves = self.get_all_valid_moves();
@@ -329,7 +270,7 @@ impl Board {
 
             let score = self.alphabeta(temp_board, depth - 1, alpha, beta, false, heuristics);
 
-            if(score < best_score) {
+            if(score > best_score) {
                 best_score = score;
                 best_move = a_move;
             }
@@ -343,7 +284,9 @@ impl Board {
     fn alphabeta(&self, board: Board, depth: u32, mut alpha: f64, mut beta: f64, is_maximizing_player: bool, heuristics: &[Heuristic]) -> f64 {
 
         if depth == 0 || board.game_state != GameState::Ongoing {
-            return board.evaluate_board(heuristics);
+            // evaluate_board scores for the side to move, which is the root player only on maximizing plies.
+            let score = board.evaluate_board(heuristics);
+            return if is_maximizing_player { score } else { -score };
         }
 
         let possible_moves = board.get_all_valid_moves();
@@ -637,13 +580,7 @@ impl Board {
                         let opponent_replies = opponent_board_view.get_all_valid_moves();
 
                         for opp_reply in &opponent_replies {
-                            let target_cell = board_after_my_move.cells[opp_reply.0][opp_reply.1];
-                            let would_explode = match target_cell.state {
-                                CellState::Occupied { orbs, .. } => orbs == target_cell.critical_mass + 1,
-                                CellState::Empty => 1 >= target_cell.critical_mass,
-                            };
-
-                            if would_explode {
+                            if board_after_my_move.cells[opp_reply.0][opp_reply.1].explodes_on_next_orb() {
                                 is_move_safe = false;
                                 break;
                             }
@@ -655,11 +592,90 @@ impl Board {
                     safe_moves
                 }
 
+                Heuristic::Mobility => {
+                    // Moves each side would have if it were their turn.
+                    let opponent = if player == Player::Red { Player::Blue } else { Player::Red };
+                    let mut my_view = self.clone();
+                    my_view.current_turn = player;
+                    let mut opponent_view = self.clone();
+                    opponent_view.current_turn = opponent;
+                    my_view.get_all_valid_moves().len() as f64 - opponent_view.get_all_valid_moves().len() as f64
+                }
+
+                Heuristic::Influence => {
+                    // Each empty cell goes to whichever side's orbs it is fewer steps from; ties go to no one.
+                    let opponent = if player == Player::Red { Player::Blue } else { Player::Red };
+                    let mine = self.steps_from(player);
+                    let theirs = self.steps_from(opponent);
+                    let mut influence_score = 0.0;
+                    for (index, cell) in self.cells.iter().flatten().enumerate() {
+                        if cell.state != CellState::Empty { continue; }
+                        match (mine[index], theirs[index]) {
+                            (Some(m), Some(t)) if m < t => influence_score += 1.0,
+                            (Some(m), Some(t)) if m > t => influence_score -= 1.0,
+                            (Some(_), None) => influence_score += 1.0,
+                            (None, Some(_)) => influence_score -= 1.0,
+                            _ => {}
+                        }
+                    }
+                    influence_score
+                }
+                Heuristic::CenterControl => {
+                    // Owned cells count more the closer (Manhattan) they sit to the middle of the board.
+                    let center_r = (self.height as f64 - 1.0) / 2.0;
+                    let center_c = (self.width as f64 - 1.0) / 2.0;
+                    let mut center_score = 0.0;
+                    for r in 0..self.height as usize {
+                        for c in 0..self.width as usize {
+                            if let CellState::Occupied { player: cell_player, .. } = self.cells[r][c].state {
+                                let distance = (r as f64 - center_r).abs() + (c as f64 - center_c).abs();
+                                let value = 1.0 / (1.0 + distance);
+                                if cell_player == player {
+                                    center_score += value;
+                                } else {
+                                    center_score -= value;
+                                }
+                            }
+                        }
+                    }
+                    center_score
+                }
+
             }
         }
         
         total_score
     }
+
+    /// Breadth-first distance from the nearest of `player`'s cells to every cell, indexed row-major.
+    /// None where `player` has no cells to start from.
+    fn steps_from(&self, player: Player) -> Vec<Option<u32>> {
+        let (width, height) = (self.width as usize, self.height as usize);
+        let neighbors_diff: [(isize, isize); 4] = [(-1, 0), (1, 0), (0, -1), (0, 1)];
+        let mut steps = vec![None; width * height];
+        let mut frontier = VecDeque::new();
+        for (index, cell) in self.cells.iter().flatten().enumerate() {
+            if matches!(cell.state, CellState::Occupied { player: owner, .. } if owner == player) {
+                steps[index] = Some(0);
+                frontier.push_back((index / width, index % width));
+            }
+        }
+        while let Some((r, c)) = frontier.pop_front() {
+            let next = steps[r * width + c].map(|s: u32| s + 1);
+            for (dr, dc) in &neighbors_diff {
+                let nr = r as isize + dr;
+                let nc = c as isize + dc;
+                if nr >= 0 && nr < height as isize && nc >= 0 && nc < width as isize {
+                    let index = nr as usize * width + nc as usize;
+                    if steps[index].is_none() {
+                        steps[index] = next;
+                        frontier.push_back((nr as usize, nc as usize));
+                    }
+                }
+            }
+        }
+        steps
+    }
 }
 
 
@@ -7,7 +7,7 @@ pub enum Player {
     Blue,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum CellState {
     Empty,
     Occupied { player: Player, orbs: u32 },
@@ -17,6 +17,8 @@ pub enum CellState {
 pub enum GameState {
     Ongoing,
     Won { winner: Player },
+    // The same position came up a third time with the same player to move.
+    Draw,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -35,6 +37,15 @@ impl Cell {
         }
     }
 
+    // True when one more orb (from whichever player) would make this cell explode.
+    pub fn explodes_on_next_orb(&self) -> bool {
+        let orbs = match self.state {
+            CellState::Occupied { orbs, .. } => orbs,
+            CellState::Empty => 0,
+        };
+        orbs + 1 >= self.critical_mass
+    }
+
     pub fn add_orb(&mut self, player: Player) -> bool {
         match self.state {
             CellState::Empty => {
@@ -69,3 +80,35 @@ impl Cell {
         self.state = CellState::Occupied { player, orbs: orbs + 1 };
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn explodes_on_next_orb_at_every_level() {
+        // (cell, critical mass, orbs already in it, whether one more orb explodes it)
+        let cases = [
+            ("corner", 2, 0, false),
+            ("corner", 2, 1, true),
+            ("edge", 3, 0, false),
+            ("edge", 3, 1, false),
+            ("edge", 3, 2, true),
+            ("centre", 4, 0, false),
+            ("centre", 4, 1, false),
+            ("centre", 4, 2, false),
+            ("centre", 4, 3, true),
+        ];
+        for (kind, critical_mass, orbs, expected) in cases {
+            let mut cell = Cell::new(critical_mass);
+            if orbs > 0 {
+                cell.state = CellState::Occupied { player: Player::Red, orbs };
+            }
+            assert_eq!(cell.explodes_on_next_orb(), expected, "{} cell with {} orbs", kind, orbs);
+
+            // Whatever the predicate says must match what actually happens when the orb lands.
+            cell.add_orb(Player::Red);
+            assert_eq!(cell.get_explosion_data().is_some(), expected, "{} cell with {} orbs", kind, orbs);
+        }
+    }
+}
@@ -0,0 +1,5 @@
+// The game, board and AI, shared by the terminal game in main.rs and the tools in src/bin.
+pub mod game;
+pub mod board;
+pub mod ai;
+pub mod selfplay;
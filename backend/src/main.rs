@@ -1,24 +1,63 @@
-// This file is now the main entry point and "director" of the application.
-// It declares the other modules and contains the game loop.
+// This file is the main entry point and "director" of the application: it contains the game loop,
+// while the game, board and AI modules live in the library (lib.rs).
 
+use std::env;
 use std::io;
+use std::process;
 
+// --- Bring necessary items into scope ---
+use backend::game::Player;
+use backend::board::Board;
+use backend::ai::{AIStrategy, Heuristic, get_ai_move};
 
-mod game;
-mod board;
-mod ai;
+/// Who picks the moves for a colour.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PlayerKind {
+    Human,
+    AI,
+}
 
-// --- Bring necessary items into scope ---
-use game::{Player, GameState};
-use board::Board;
-use ai::{AIStrategy, Heuristic, get_ai_move};
+impl PlayerKind {
+    fn parse(arg: &str) -> Option<PlayerKind> {
+        match arg.to_ascii_lowercase().as_str() {
+            "human" => Some(PlayerKind::Human),
+            "ai" => Some(PlayerKind::AI),
+            _ => None,
+        }
+    }
+}
+
+/// Reads `[red] [blue]` from the command line, each "human" or "ai". Missing seats keep the
+/// default Human (Red) vs. AI (Blue) match.
+fn parse_player_kinds(args: &[String]) -> Result<[PlayerKind; 2], String> {
+    if args.len() > 2 {
+        return Err(format!("Expected at most 2 arguments, got {}", args.len()));
+    }
+    let mut kinds = [PlayerKind::Human, PlayerKind::AI];
+    for (kind, arg) in kinds.iter_mut().zip(args) {
+        *kind = PlayerKind::parse(arg).ok_or_else(|| format!("Unknown player kind '{}'", arg))?;
+    }
+    Ok(kinds)
+}
 
-/// The main game loop for a Human vs. AI match.
+/// The main game loop. Each colour is played by a human at the terminal or by the AI.
 fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let player_kinds = match parse_player_kinds(&args) {
+        Ok(kinds) => kinds,
+        Err(e) => {
+            eprintln!("{}", e);
+            eprintln!("Usage: backend [human|ai] [human|ai]   (Red first, then Blue)");
+            process::exit(2);
+        }
+    };
+    let kind_of = |player: Player| match player {
+        Player::Red => player_kinds[0],
+        Player::Blue => player_kinds[1],
+    };
+
     let log_filename = "game_log.txt".to_string();
     let mut game_board = Board::new(6, 9, Player::Red, log_filename);
-    let human_player = Player::Red;
-    let ai_player = Player::Blue;
 
     // --- AI Configuration ---
     let ai_strategy = AIStrategy::AlphaBeta; 
@@ -29,25 +68,36 @@ fn main() {
         // Heuristic::ConversionPotential,
         //Heuristic::SafeMobility,
         //Heuristic::CascadePotential,
+        //Heuristic::Mobility,
+        //Heuristic::Influence,
+        //Heuristic::CenterControl,
     ];
     let search_depth = 2; // A depth of 4-5 is a good starting point.
+    let time_limit_ms = Some(5000); // Deepening stops at whichever comes first, depth or time.
 
-    println!("You are Player {:?}. The AI is Player {:?}.", human_player, ai_player);
+    println!("Red is {:?}. Blue is {:?}.", kind_of(Player::Red), kind_of(Player::Blue));
 
     loop {
-        if let GameState::Won { winner } = game_board.game_state {
-            println!("\n--- GAME OVER ---"); 
-            println!("Player {:?} has won!", winner);
+        if game_board.is_over() {
+            println!("\n--- GAME OVER ---");
+            match game_board.winner() {
+                Some(winner) => println!("Player {:?} has won!", winner),
+                None => println!("Draw by repetition."),
+            }
             game_board.print();
             break;
         }
 
         game_board.print();
         let current_player = game_board.current_turn;
-        if current_player == human_player {
-            println!("Your turn (enter 'row col'): ");
+        if kind_of(current_player) == PlayerKind::Human {
+            println!("{:?}'s turn (enter 'row col'): ", current_player);
             let mut input = String::new();
-            io::stdin().read_line(&mut input).expect("Failed to read line");
+            if io::stdin().read_line(&mut input).expect("Failed to read line") == 0 {
+                // stdin closed (e.g. piped input ran out), so no more moves are coming.
+                println!("No more input. Exiting.");
+                break;
+            }
 
             let parts: Vec<Result<usize, _>> = input.trim().split_whitespace().map(|s| s.parse()).collect();
 
@@ -63,10 +113,11 @@ fn main() {
                 println!("Invalid input. Please use the format 'row col', e.g., '3 4'");
             }
         } else {
-            println!("AI ({:?}) is thinking...", ai_player);
+            println!("AI ({:?}) is thinking...", current_player);
             // UPDATED CALL: We now call the free function from the `ai` module.
-            let (row, col) = get_ai_move(&game_board, ai_strategy, &ai_heuristics, search_depth);
-            println!("AI moves to ({}, {})", row, col);
+            let stats = get_ai_move(&game_board, ai_strategy, &ai_heuristics, search_depth, time_limit_ms);
+            let (row, col) = stats.chosen;
+            println!("AI moves to ({}, {}): depth {}, {} nodes in {} ms", row, col, stats.depth_reached, stats.nodes, stats.elapsed.as_millis());
             game_board.log_move(current_player, row, col);
             game_board.make_move(row, col).expect("AI made an invalid move!");
         }
@@ -0,0 +1,114 @@
+// The AI-vs-AI harness behind the bench binary: config parsing, per-config tallies and the game
+// loop itself.
+
+use std::time::Duration;
+
+use crate::ai::{AIStrategy, Heuristic, SearchStats, get_ai_move};
+use crate::board::Board;
+use crate::game::{GameState, Player};
+use rand::Rng;
+
+pub const DEFAULT_DEPTH: u32 = 3;
+// Alpha-beta is deterministic, so each game opens with a few random moves to keep games apart.
+const RANDOM_OPENING_PLIES: u32 = 2;
+// A game still going after this many moves is scored as a draw.
+const MOVE_CAP: u32 = 1000;
+
+#[derive(Debug, Clone)]
+pub struct AIConfig {
+    pub label: String,
+    pub strategy: AIStrategy,
+    pub depth: u32,
+    pub heuristics: Vec<Heuristic>,
+}
+
+/// Running totals for one config across every game.
+#[derive(Debug, Default)]
+pub struct Tally {
+    pub wins: u32,
+    pub moves: u64,
+    pub nodes: u64,
+    pub depth: u64,
+    pub time: Duration,
+}
+
+impl Tally {
+    pub fn record(&mut self, stats: &SearchStats) {
+        self.moves += 1;
+        self.nodes += stats.nodes;
+        self.depth += stats.depth_reached as u64;
+        self.time += stats.elapsed;
+    }
+}
+
+pub fn parse_heuristic(name: &str) -> Option<Heuristic> {
+    match name {
+        "OrbDifference" => Some(Heuristic::OrbDifference),
+        "PeripheralControl" => Some(Heuristic::PeripheralControl),
+        "TerritoryControl" => Some(Heuristic::TerritoryControl),
+        "ChainReactionPotential" => Some(Heuristic::ChainReactionPotential),
+        "ConversionPotential" => Some(Heuristic::ConversionPotential),
+        "CascadePotential" => Some(Heuristic::CascadePotential),
+        "SafeMobility" => Some(Heuristic::SafeMobility),
+        "Mobility" => Some(Heuristic::Mobility),
+        "Influence" => Some(Heuristic::Influence),
+        "CenterControl" => Some(Heuristic::CenterControl),
+        _ => None,
+    }
+}
+
+pub fn parse_config(arg: &str) -> Result<AIConfig, String> {
+    let mut parts = arg.split(':');
+    let strategy = match parts.next().map(str::to_ascii_lowercase).as_deref() {
+        Some("random") => AIStrategy::Random,
+        Some("alphabeta") => AIStrategy::AlphaBeta,
+        _ => return Err(format!("Unknown strategy in '{}'", arg)),
+    };
+    let depth = match parts.next() {
+        Some(depth) => depth.parse().map_err(|_| format!("Invalid depth in '{}'", arg))?,
+        None => DEFAULT_DEPTH,
+    };
+    let heuristics = match parts.next() {
+        Some(names) => names.split(',')
+            .map(|name| parse_heuristic(name).ok_or_else(|| format!("Unknown heuristic '{}'", name)))
+            .collect::<Result<Vec<_>, _>>()?,
+        None => vec![Heuristic::OrbDifference],
+    };
+    Ok(AIConfig { label: arg.to_string(), strategy, depth, heuristics })
+}
+
+pub fn parse_size(arg: &str) -> Result<(u32, u32), String> {
+    let (width, height) = arg.split_once('x').ok_or_else(|| format!("Expected WIDTHxHEIGHT, got '{}'", arg))?;
+    let width = width.parse().map_err(|_| format!("Invalid width in '{}'", arg))?;
+    let height = height.parse().map_err(|_| format!("Invalid height in '{}'", arg))?;
+    if width < 2 || height < 2 {
+        return Err(format!("Board must be at least 2x2, got {}", arg));
+    }
+    Ok((width, height))
+}
+
+/// Plays one game with `configs[0]` as Red and returns the winner's index into `configs`, or None
+/// for a draw. The opening plies are drawn from `rng`, so a seeded rng replays the same game.
+pub fn play_game(configs: [&AIConfig; 2], tallies: &mut [Tally; 2], size: (u32, u32), log_file: &str, rng: &mut impl Rng) -> Option<usize> {
+    let mut board = Board::new(size.0, size.1, Player::Red, log_file.to_string());
+
+    while board.game_state == GameState::Ongoing && board.total_moves < MOVE_CAP {
+        let seat = if board.current_turn == Player::Red { 0 } else { 1 };
+        let (row, col) = if board.total_moves < RANDOM_OPENING_PLIES {
+            let moves = board.get_all_valid_moves();
+            moves[rng.random_range(0..moves.len())]
+        } else {
+            let config = configs[seat];
+            let stats = get_ai_move(&board, config.strategy, &config.heuristics, config.depth, None);
+            tallies[seat].record(&stats);
+            stats.chosen
+        };
+        board.make_move(row, col).expect("AI made an invalid move!");
+    }
+
+    match board.game_state {
+        GameState::Won { winner: Player::Red } => Some(0),
+        GameState::Won { winner: Player::Blue } => Some(1),
+        GameState::Ongoing | GameState::Draw => None,
+    }
+}
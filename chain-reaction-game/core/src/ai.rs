@@ -0,0 +1,2159 @@
+use crate::board::{Board, MoveError};
+use crate::game::{Player, GameState, CellState, Topology};
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::sync::OnceLock;
+use web_time::{Instant, Duration};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AIStrategy {
+    Random,
+    AlphaBeta,
+    // The same search as AlphaBeta with every cutoff disabled, to show how much pruning saves.
+    Minimax,
+    // Ignores defence and always sets off the biggest chain reaction it can.
+    Berserk,
+}
+
+// Settings that change how a position is scored, independent of which heuristics are enabled.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct EvalOptions {
+    // Score every heuristic as (mine - opponent's) so the evaluation is strictly anti-symmetric.
+    pub zero_sum: bool,
+    pub weights: HeuristicWeights,
+}
+
+// The multiplier evaluate_board applies to each heuristic. Defaults to the hand-tuned W_* values,
+// including for any field missing from a deserialized config.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HeuristicWeights {
+    pub orb_difference: f64,
+    pub peripheral_control: f64,
+    pub territory_control: f64,
+    pub chain_reaction_potential: f64,
+    pub conversion_potential: f64,
+    pub cascade_potential: f64,
+    pub safe_mobility: f64,
+    pub mobility: f64,
+    pub influence: f64,
+    pub vulnerability: f64,
+    pub center_control: f64,
+    // Not a heuristic: the always-on bonus for pushing an opponent toward elimination.
+    pub near_win: f64,
+}
+
+impl Default for HeuristicWeights {
+    fn default() -> Self {
+        HeuristicWeights {
+            orb_difference: W_ORB_DIFF,
+            peripheral_control: W_PERIPHERAL,
+            territory_control: W_TERRITORY,
+            chain_reaction_potential: W_CHAIN_POTENTIAL,
+            conversion_potential: W_CONVERSION,
+            cascade_potential: W_CASCADE,
+            safe_mobility: W_SAFE_MOBILITY,
+            mobility: W_MOBILITY,
+            influence: W_INFLUENCE,
+            vulnerability: W_VULNERABILITY,
+            center_control: W_CENTER,
+            near_win: W_NEAR_WIN,
+        }
+    }
+}
+
+impl HeuristicWeights {
+    pub fn get(&self, heuristic: Heuristic) -> f64 {
+        match heuristic {
+            Heuristic::OrbDifference => self.orb_difference,
+            Heuristic::PeripheralControl => self.peripheral_control,
+            Heuristic::TerritoryControl => self.territory_control,
+            Heuristic::ChainReactionPotential => self.chain_reaction_potential,
+            Heuristic::ConversionPotential => self.conversion_potential,
+            Heuristic::CascadePotential => self.cascade_potential,
+            Heuristic::SafeMobility => self.safe_mobility,
+            Heuristic::Mobility => self.mobility,
+            Heuristic::Influence => self.influence,
+            Heuristic::Vulnerability => self.vulnerability,
+            Heuristic::CenterControl => self.center_control,
+        }
+    }
+
+    pub fn get_mut(&mut self, heuristic: Heuristic) -> &mut f64 {
+        match heuristic {
+            Heuristic::OrbDifference => &mut self.orb_difference,
+            Heuristic::PeripheralControl => &mut self.peripheral_control,
+            Heuristic::TerritoryControl => &mut self.territory_control,
+            Heuristic::ChainReactionPotential => &mut self.chain_reaction_potential,
+            Heuristic::ConversionPotential => &mut self.conversion_potential,
+            Heuristic::CascadePotential => &mut self.cascade_potential,
+            Heuristic::SafeMobility => &mut self.safe_mobility,
+            Heuristic::Mobility => &mut self.mobility,
+            Heuristic::Influence => &mut self.influence,
+            Heuristic::Vulnerability => &mut self.vulnerability,
+            Heuristic::CenterControl => &mut self.center_control,
+        }
+    }
+
+    // Negative (or NaN) weights become zero, so a bad config can't flip what the AI aims for.
+    pub fn clamped(mut self) -> Self {
+        for weight in [
+            &mut self.orb_difference, &mut self.peripheral_control, &mut self.territory_control,
+            &mut self.chain_reaction_potential, &mut self.conversion_potential, &mut self.cascade_potential,
+            &mut self.safe_mobility, &mut self.mobility, &mut self.influence, &mut self.vulnerability,
+            &mut self.center_control, &mut self.near_win,
+        ] {
+            *weight = weight.max(0.0);
+        }
+        self
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Heuristic {
+    OrbDifference,
+    PeripheralControl,
+    TerritoryControl,
+    ChainReactionPotential,
+    ConversionPotential,
+    CascadePotential,
+    SafeMobility,
+    // Legal moves minus the opponent's.
+    Mobility,
+    // Empty cells nearer to my orbs than the opponent's, minus the reverse.
+    Influence,
+    // Minus the orbs in my primed cells that a primed opponent neighbour could explode into next turn.
+    Vulnerability,
+    // Owned cells weighted by closeness to the middle of the board: PeripheralControl's opposite bias.
+    CenterControl,
+}
+
+impl Heuristic {
+    pub const ALL: [Heuristic; 11] = [
+        Heuristic::OrbDifference, Heuristic::PeripheralControl, Heuristic::TerritoryControl,
+        Heuristic::ChainReactionPotential, Heuristic::ConversionPotential, Heuristic::CascadePotential,
+        Heuristic::SafeMobility, Heuristic::Mobility, Heuristic::Influence, Heuristic::Vulnerability,
+        Heuristic::CenterControl,
+    ];
+}
+
+// How the root search picks among moves that score exactly the same.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TieBreak {
+    // The first one searched: the previous depth's best move, then move-ordering and grid order.
+    #[default]
+    First,
+    // The one nearest a corner.
+    NearCorner,
+    // The one whose resulting position the static evaluation likes best.
+    Shallow,
+    // One drawn from an RNG seeded with this value, so a seeded game replays the same choices.
+    Random(u64),
+}
+
+// What one AI decision cost: how deep the search got before the time limit, how long it took and how
+// many positions it visited. Moves picked without a search (random, berserk, opening book) report
+// depth 0, no nodes and a NaN score.
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct SearchStats {
+    pub chosen: (usize, usize),
+    // The chosen move's score from the mover's point of view, as in `SearchResult`.
+    pub score: f64,
+    pub depth_reached: u32,
+    pub elapsed_ms: u64,
+    pub nodes: u64,
+}
+
+impl SearchStats {
+    fn unsearched(chosen: (usize, usize), start: Instant) -> Self {
+        SearchStats { chosen, score: f64::NAN, depth_reached: 0, elapsed_ms: start.elapsed().as_millis() as u64, nodes: 0 }
+    }
+
+    fn from_search(result: SearchResult, start: Instant) -> Self {
+        SearchStats { chosen: result.chosen, score: result.score, depth_reached: result.depth_reached, elapsed_ms: start.elapsed().as_millis() as u64, nodes: result.nodes_visited }
+    }
+}
+
+// Returns None when the side to move has no legal moves; the board treats that as game over.
+pub fn get_ai_move(board: &Board, strategy: AIStrategy, heuristics: &[Heuristic], options: EvalOptions, max_depth: u32, time_limit_ms: u64) -> Option<SearchStats> {
+    get_ai_move_with_rng(board, strategy, heuristics, options, max_depth, time_limit_ms, &mut rand::thread_rng())
+}
+
+// Like `get_ai_move`, but every random choice is drawn from `rng` so a seeded caller gets reproducible moves.
+pub fn get_ai_move_with_rng<R: Rng>(board: &Board, strategy: AIStrategy, heuristics: &[Heuristic], options: EvalOptions, max_depth: u32, time_limit_ms: u64, rng: &mut R) -> Option<SearchStats> {
+    let start = Instant::now();
+    let possible_moves = board.get_all_valid_moves();
+    if possible_moves.is_empty() { return None; }
+    if matches!(strategy, AIStrategy::AlphaBeta | AIStrategy::Minimax) {
+        if let Some(book_move) = opening_book_move(board) {
+            return Some(SearchStats::unsearched(book_move, start));
+        }
+    }
+
+    match strategy {
+        AIStrategy::Random => {
+            random_move(board, rng).map(|a_move| SearchStats::unsearched(a_move, start))
+        }
+        AIStrategy::Berserk => {
+            berserk_move(board).map(|a_move| SearchStats::unsearched(a_move, start))
+        }
+        AIStrategy::AlphaBeta => {
+            alphabeta_move(board, heuristics, options, max_depth, time_limit_ms, TieBreak::First, &mut TranspositionTable::default())
+        }
+        AIStrategy::Minimax => {
+            let params = SearchParams { max_depth: recommended_depth(board, max_depth), time_limit_ms, pruning: false, tie_break: TieBreak::First };
+            search_move(board, heuristics, options, params, &mut TranspositionTable::default())
+                .map(|result| SearchStats::from_search(result, start))
+        }
+    }
+}
+
+/// Whether a search judged the position bad enough to concede: its best score fell below `threshold`.
+/// A proven loss scores negative infinity and so always qualifies. Unsearched moves never resign.
+pub fn should_resign(stats: &SearchStats, threshold: f64) -> bool {
+    stats.depth_reached > 0 && stats.score < threshold
+}
+
+// Opening placements keyed by (width, height, move number), for the preset board sizes. A corner has
+// the lowest critical mass and can only be attacked from two sides, so each player opens in one.
+type OpeningBook = HashMap<(u32, u32, u32), (usize, usize)>;
+
+fn opening_book() -> &'static OpeningBook {
+    static BOOK: OnceLock<OpeningBook> = OnceLock::new();
+    BOOK.get_or_init(|| {
+        let mut book = HashMap::new();
+        for (width, height) in [(6, 9), (5, 5), (10, 10)] {
+            book.insert((width, height, 0), (0, 0));
+            book.insert((width, height, 1), (height as usize - 1, width as usize - 1));
+        }
+        book
+    })
+}
+
+/// The opening book's move for this position, if it has one and it is legal here. Searching the first
+/// moves is slow and tells the AI little, since there is nothing to capture yet.
+pub fn opening_book_move(board: &Board) -> Option<(usize, usize)> {
+    if board.topology != Topology::Grid { return None; }
+    let book_move = *opening_book().get(&(board.width, board.height, board.total_moves))?;
+    board.get_all_valid_moves().contains(&book_move).then_some(book_move)
+}
+
+// A finished search: the move, its score from the side to move's point of view at the deepest
+// completed depth (NaN if not even depth 1 finished in time, when `depth_reached` is 0), and the
+// positions visited on the way.
+#[derive(Debug, Clone, Copy)]
+pub struct SearchResult {
+    pub chosen: (usize, usize),
+    pub score: f64,
+    pub depth_reached: u32,
+    pub nodes_visited: u64,
+}
+
+// Positions with at least this many legal moves are searched a ply shallower than configured, and
+// those with fewer than NARROW_POSITION_MOVES a ply deeper.
+const WIDE_POSITION_MOVES: usize = 50;
+const NARROW_POSITION_MOVES: usize = 15;
+
+/// The depth an AI configured for `base_depth` should search this position to. A wide early board
+/// costs far more per ply than a crowded late one, so the same time limit buys less depth early on
+/// and more later. Never below 1.
+pub fn recommended_depth(board: &Board, base_depth: u32) -> u32 {
+    let moves = board.get_all_valid_moves().len();
+    let depth = if moves >= WIDE_POSITION_MOVES {
+        base_depth.saturating_sub(1)
+    } else if moves < NARROW_POSITION_MOVES {
+        base_depth + 1
+    } else {
+        base_depth
+    };
+    depth.max(1)
+}
+
+// Iterative-deepening alpha-beta to the `recommended_depth` for `max_depth`. Pass a table that outlives
+// this call (e.g. `AICaches::transpositions`) to reuse positions searched on earlier turns; None if the
+// side to move has no legal moves.
+pub fn alphabeta_move(board: &Board, heuristics: &[Heuristic], options: EvalOptions, max_depth: u32, time_limit_ms: u64, tie_break: TieBreak, table: &mut TranspositionTable) -> Option<SearchStats> {
+    let start = Instant::now();
+    let params = SearchParams { max_depth: recommended_depth(board, max_depth), time_limit_ms, pruning: true, tie_break };
+    search_move(board, heuristics, options, params, table).map(|result| SearchStats::from_search(result, start))
+}
+
+// How far and how `search_move` searches.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SearchParams {
+    pub max_depth: u32,
+    pub time_limit_ms: u64,
+    // Alpha-beta cutoffs and transposition-table probes; off for plain minimax.
+    pub pruning: bool,
+    pub tie_break: TieBreak,
+}
+
+// The iterative-deepening driver behind AlphaBeta (`pruning`) and Minimax (no cutoffs and no
+// transposition table, so every node of the tree is visited).
+pub fn search_move(board: &Board, heuristics: &[Heuristic], options: EvalOptions, params: SearchParams, table: &mut TranspositionTable) -> Option<SearchResult> {
+    let SearchParams { max_depth, time_limit_ms, pruning, tie_break } = params;
+    let possible_moves = board.get_all_valid_moves();
+    if possible_moves.is_empty() { return None; }
+    let deadline = Instant::now() + Duration::from_millis(time_limit_ms);
+    let mut context = SearchContext::new(table, heuristics, options, deadline, pruning);
+    context.tie_break = tie_break;
+
+    let mut best_move_so_far = possible_moves[0];
+    let mut best_score_so_far = f64::NAN;
+    let mut depth_reached = 0;
+    let mut pv_move = None;
+
+    // Depth 0 still searches one ply, scoring each root move by the board it leaves, rather than
+    // handing back the first legal move unexamined.
+    for d in 1..=max_depth.max(1) {
+        if Instant::now() >= deadline {
+            break;
+        }
+
+        let result = aspiration_search(board, d, pv_move, best_score_so_far, &mut context);
+        if let Some((found_move, score)) = result {
+            best_move_so_far = found_move;
+            best_score_so_far = score;
+            depth_reached = d;
+            pv_move = Some(found_move);
+        } else {
+            // Timed out: keep the best move from the previous depth.
+            break;
+        }
+    }
+
+    Some(SearchResult { chosen: best_move_so_far, score: best_score_so_far, depth_reached, nodes_visited: context.nodes_visited })
+}
+
+// State shared by every node of one search.
+struct SearchContext<'a> {
+    table: &'a mut TranspositionTable,
+    heuristics: &'a [Heuristic],
+    options: EvalOptions,
+    // Past this the search gives up and its caller keeps the previous depth's result.
+    deadline: Instant,
+    // Alpha-beta cutoffs and transposition-table probes; off for plain minimax.
+    pruning: bool,
+    nodes_visited: u64,
+    // The last two moves that caused a cutoff at each remaining depth, newest first. A move that
+    // refuted one sibling often refutes the next.
+    killers: Vec<[Option<(usize, usize)>; 2]>,
+    // Cutoffs per cell across the whole search, weighted by depth squared so cutoffs near the root
+    // count for more.
+    history: HashMap<(usize, usize), i32>,
+    // Whether cutoffs feed the killers and history at all; only turned off to measure what they save.
+    learn_ordering: bool,
+    // Only consulted at the root.
+    tie_break: TieBreak,
+}
+
+impl<'a> SearchContext<'a> {
+    fn new(table: &'a mut TranspositionTable, heuristics: &'a [Heuristic], options: EvalOptions, deadline: Instant, pruning: bool) -> Self {
+        SearchContext { table, heuristics, options, deadline, pruning, nodes_visited: 0, killers: Vec::new(), history: HashMap::new(), learn_ordering: true, tie_break: TieBreak::First }
+    }
+
+    fn timed_out(&self) -> bool {
+        Instant::now() >= self.deadline
+    }
+
+    fn evaluate(&self, board: &Board) -> f64 {
+        evaluate_board(board, self.heuristics, self.options, side_to_move(board))
+    }
+
+    // Drops the table's entries if they were scored by a different evaluator.
+    fn prepare_table(&mut self, board: &Board) {
+        self.table.prepare_for(board, self.heuristics, self.options);
+    }
+
+    fn is_killer(&self, depth: u32, a_move: (usize, usize)) -> bool {
+        self.killers.get(depth as usize).is_some_and(|slots| slots.contains(&Some(a_move)))
+    }
+
+    fn record_cutoff(&mut self, depth: u32, a_move: (usize, usize)) {
+        if !self.learn_ordering { return; }
+        let depth_index = depth as usize;
+        if self.killers.len() <= depth_index {
+            self.killers.resize(depth_index + 1, [None; 2]);
+        }
+        let slots = &mut self.killers[depth_index];
+        if slots[0] != Some(a_move) {
+            slots[1] = slots[0];
+            slots[0] = Some(a_move);
+        }
+        *self.history.entry(a_move).or_insert(0) += (depth * depth) as i32;
+    }
+}
+
+// One ply only: the move with the longest cascade, ties broken by the most opponent orbs captured.
+fn berserk_move(board: &Board) -> Option<(usize, usize)> {
+    let opponents: Vec<Player> = board.opponents(board.current_turn).collect();
+    let opponent_orbs = |board: &Board| opponents.iter().map(|opponent| board.orb_counts[opponent]).sum::<u32>();
+    let opponent_orbs_before = opponent_orbs(board);
+
+    let mut best: Option<((usize, usize), (usize, u32))> = None;
+    for a_move in board.get_all_valid_moves() {
+        let mut temp_board = board.clone();
+        let Ok(explosions) = temp_board.make_move_counting_explosions(a_move.0, a_move.1) else { continue; };
+        let captured = opponent_orbs_before.saturating_sub(opponent_orbs(&temp_board));
+
+        if best.is_none_or(|(_, best_key)| (explosions, captured) > best_key) {
+            best = Some((a_move, (explosions, captured)));
+        }
+    }
+    best.map(|(a_move, _)| a_move)
+}
+
+// Half-width of the aspiration window, in orbs' worth of evaluation either side of the previous
+// depth's score. The weighted heuristics often move the score by several orbs from one depth to the
+// next, and a narrower window spends more on re-searches than it saves.
+const ASPIRATION_DELTA: f64 = 10.0 * W_ORB_DIFF;
+
+// Root scores within this of each other count as a tie for `TieBreak`.
+const TIE_EPSILON: f64 = 1e-9;
+
+// Root moves are deduplicated by symmetry for this many plies; after that the position is almost
+// never symmetric and the check isn't worth it.
+const SYMMETRY_DEDUP_MOVES: u32 = 4;
+
+// One depth of the iterative deepening, searched close to the previous depth's score: that prunes far
+// more than a full window when the score barely moves. A result outside the window is only a bound, so
+// that side is opened up and the depth searched again. Plain minimax, the first depth (NaN previous
+// score) and won/lost scores use the full window.
+fn aspiration_search(board: &Board, depth: u32, pv_move: Option<(usize, usize)>, previous_score: f64, context: &mut SearchContext) -> Option<((usize, usize), f64)> {
+    let mut window = if context.pruning && previous_score.is_finite() {
+        (previous_score - ASPIRATION_DELTA, previous_score + ASPIRATION_DELTA)
+    } else {
+        (f64::NEG_INFINITY, f64::INFINITY)
+    };
+    loop {
+        match find_best_move_at_depth(board, depth, pv_move, window, context) {
+            Some((_, score)) if score <= window.0 && window.0 > f64::NEG_INFINITY => window.0 = f64::NEG_INFINITY,
+            Some((_, score)) if score >= window.1 && window.1 < f64::INFINITY => window.1 = f64::INFINITY,
+            result => return result,
+        }
+    }
+}
+
+// Returns the best root move together with its score from the side-to-move's point of view,
+// or None if the search timed out or there is no legal move. `pv_move` (the previous depth's best
+// move) is searched first, so the window is usually tight before the other moves are tried. A score
+// at or outside `window` is only a bound on the true one. Moves tying for best are settled by the
+// context's `TieBreak`.
+fn find_best_move_at_depth(board: &Board, depth: u32, pv_move: Option<(usize, usize)>, window: (f64, f64), context: &mut SearchContext) -> Option<((usize, usize), f64)> {
+    let mut best_move: (usize, usize);
+    let mut best_score = f64::NEG_INFINITY; 
+    let mut tied_moves = Vec::new();
+
+    let (mut alpha, beta) = window;
+    
+    // Early on the board is often still symmetric, and mirrored root moves need only be searched once.
+    let root_moves = if board.total_moves < SYMMETRY_DEDUP_MOVES { board.canonical_moves() } else { board.get_all_valid_moves() };
+    let mut possible_moves = order_moves(board, root_moves);
+    if possible_moves.is_empty() { return None; }
+    if let Some(index) = pv_move.and_then(|pv| possible_moves.iter().position(|&m| m == pv)) {
+        let pv = possible_moves.remove(index);
+        possible_moves.insert(0, pv);
+    }
+
+    best_move = possible_moves[0];
+    
+    context.prepare_table(board);
+
+    for a_move in possible_moves {
+        if context.timed_out() {
+            return None; 
+        }
+
+        let mut temp_board = board.clone();
+        
+        if temp_board.make_move_for_simulation(a_move.0, a_move.1, Some(&context.deadline)).is_err() {
+            continue; 
+        }
+
+        match negamax(&temp_board, depth - 1, -beta, -alpha, context) {
+            Ok(child_score) => {
+                let score = -child_score;
+                if score > best_score + TIE_EPSILON {
+                    best_score = score;
+                    best_move = a_move;
+                    tied_moves.clear();
+                    tied_moves.push(a_move);
+                } else if score >= best_score - TIE_EPSILON {
+                    tied_moves.push(a_move);
+                }
+                // Breaking ties needs exact scores for the equal moves, not just "no better" bounds,
+                // so the window stays open just below the best score.
+                alpha = if context.tie_break == TieBreak::First { alpha.max(best_score) } else { alpha.max(best_score - 2.0 * TIE_EPSILON) };
+                if alpha >= beta {
+                    break;
+                }
+            },
+            Err(_) => {
+                return None;
+            }
+        }
+    }
+    if tied_moves.len() > 1 && context.tie_break != TieBreak::First {
+        best_move = break_tie(board, context.heuristics, context.options, &tied_moves, context.tie_break);
+    }
+    Some((best_move, best_score))
+}
+
+// Picks one of `tied_moves` (in search order, never empty) for `tie_break`.
+fn break_tie(board: &Board, heuristics: &[Heuristic], options: EvalOptions, tied_moves: &[(usize, usize)], tie_break: TieBreak) -> (usize, usize) {
+    let (height, width) = (board.height as usize, board.width as usize);
+    let first_best = |key: &dyn Fn((usize, usize)) -> f64| {
+        tied_moves.iter().copied().fold(None, |best: Option<((usize, usize), f64)>, a_move| {
+            let value = key(a_move);
+            if best.is_none_or(|(_, best_value)| value > best_value) { Some((a_move, value)) } else { best }
+        }).map(|(a_move, _)| a_move)
+    };
+    let chosen = match tie_break {
+        TieBreak::First => None,
+        TieBreak::NearCorner => first_best(&|(r, c)| -((r.min(height - 1 - r) + c.min(width - 1 - c)) as f64)),
+        TieBreak::Shallow => first_best(&|(r, c)| {
+            let mut temp_board = board.clone();
+            match temp_board.make_move_for_simulation(r, c, None) {
+                Ok(_) => evaluate_board(&temp_board, heuristics, options, board.current_turn),
+                Err(_) => f64::NEG_INFINITY,
+            }
+        }),
+        TieBreak::Random(seed) => {
+            let mut rng = StdRng::seed_from_u64(seed);
+            Some(tied_moves[rng.gen_range(0..tied_moves.len())])
+        }
+    };
+    chosen.unwrap_or(tied_moves[0])
+}
+
+// Depth and time budget used by the analysis helpers below. They run outside a real turn,
+// so a shallow search is enough to show the trend without stalling the UI.
+pub const ANALYSIS_DEPTH: u32 = 2;
+const ANALYSIS_TIME_LIMIT_MS: u64 = 5000;
+
+// Scores a single root move by searching the position it leads to, from the mover's point of view.
+fn score_root_move(board: &Board, a_move: (usize, usize), depth: u32, context: &mut SearchContext) -> Result<f64, ()> {
+    context.prepare_table(board);
+    let mut temp_board = board.clone();
+    temp_board.make_move_for_simulation(a_move.0, a_move.1, Some(&context.deadline)).map_err(|_| ())?;
+    Ok(-negamax(&temp_board, depth - 1, f64::NEG_INFINITY, f64::INFINITY, context)?)
+}
+
+/// For each heuristic, reports how much the chosen move's score drops when that heuristic's
+/// weight is zeroed. The heuristic with the largest change is the one driving the decision.
+pub fn heuristic_sensitivity(board: &Board, heuristics: &[Heuristic], options: EvalOptions) -> Result<Vec<(Heuristic, f64)>, &'static str> {
+    if board.get_all_valid_moves().is_empty() {
+        return Err("No legal moves to analyse.");
+    }
+    let deadline = Instant::now() + Duration::from_millis(ANALYSIS_TIME_LIMIT_MS);
+    let mut table = TranspositionTable::default();
+    let mut context = SearchContext::new(&mut table, heuristics, options, deadline, true);
+
+    let (chosen_move, full_score) = find_best_move_at_depth(board, ANALYSIS_DEPTH, None, (f64::NEG_INFINITY, f64::INFINITY), &mut context)
+        .ok_or("Analysis timed out.")?;
+
+    let mut sensitivity = Vec::with_capacity(heuristics.len());
+    for (i, heuristic) in heuristics.iter().enumerate() {
+        let mut reduced = heuristics.to_vec();
+        reduced.remove(i);
+
+        // The table is only valid for one set of heuristics, so each reduced search starts afresh.
+        let mut reduced_context = SearchContext::new(&mut table, &reduced, options, deadline, true);
+        let reduced_score = score_root_move(board, chosen_move, ANALYSIS_DEPTH, &mut reduced_context)
+            .map_err(|_| "Analysis timed out.")?;
+        // Winning lines score infinity either way; treat that as "no change" rather than NaN.
+        let change = if full_score == reduced_score { 0.0 } else { full_score - reduced_score };
+        sensitivity.push((*heuristic, change));
+    }
+    Ok(sensitivity)
+}
+
+// The player a position is scored for in negamax: whoever is to move, or a loser once the game is won
+// (the winner made the last move, so the turn never passed on). With more than two players the first
+// loser in turn order stands in for the rest.
+fn side_to_move(board: &Board) -> Player {
+    match board.game_state {
+        GameState::Won { winner } => board.players().find(|&player| player != winner).unwrap_or(winner),
+        GameState::Ongoing | GameState::Draw => board.current_turn,
+    }
+}
+
+// Alpha-beta in negamax form: every score is from the side to move's point of view, so a child's
+// score is negated and the window flipped on the way down.
+fn negamax(board: &Board, depth: u32, mut alpha: f64, beta: f64, context: &mut SearchContext) -> Result<f64, ()> {
+    if context.timed_out() {
+        return Err(());
+    }
+    context.nodes_visited += 1;
+
+    if board.is_over() {
+        return Ok(context.evaluate(board));
+    }
+    if depth == 0 {
+        return quiesce(board, alpha, beta, QUIESCENCE_MAX_DEPTH, context);
+    }
+
+    let possible_moves = board.get_all_valid_moves();
+    if possible_moves.is_empty() {
+        return Ok(context.evaluate(board));
+    }
+
+    let hash = board.zobrist_hash();
+    if context.pruning {
+        if let Some(score) = context.table.probe(hash, depth, alpha, beta) {
+            return Ok(score);
+        }
+    }
+    let alpha_orig = alpha;
+
+    let mut best = f64::NEG_INFINITY;
+    for a_move in order_moves_with_context(board, possible_moves, depth, context) {
+        let mut child_board = board.clone();
+        child_board.make_move_for_simulation(a_move.0, a_move.1, Some(&context.deadline)).map_err(|_| ())?;
+
+        let eval = -negamax(&child_board, depth - 1, -beta, -alpha, context)?;
+        best = best.max(eval);
+        alpha = alpha.max(eval);
+        if context.pruning && alpha >= beta {
+            context.record_cutoff(depth, a_move);
+            break;
+        }
+    }
+
+    if context.pruning {
+        context.table.store(hash, depth, best, alpha_orig, beta);
+    }
+    Ok(best)
+}
+
+// How many plies of captures quiescence search may add below the nominal depth. Chain reactions can
+// trade captures back and forth for a long time, so the extension has to stop somewhere.
+const QUIESCENCE_MAX_DEPTH: u32 = 4;
+
+// Extends the search past depth 0 while the side to move can capture: only those "loud" moves are
+// tried, so a position is never scored halfway through an exchange. The side to move may also
+// "stand pat" on the static evaluation, since it could play a quiet move instead.
+fn quiesce(board: &Board, mut alpha: f64, beta: f64, depth_left: u32, context: &mut SearchContext) -> Result<f64, ()> {
+    let stand_pat = context.evaluate(board);
+    if depth_left == 0 || board.is_over() {
+        return Ok(stand_pat);
+    }
+    if context.pruning && stand_pat >= beta {
+        return Ok(stand_pat);
+    }
+    alpha = alpha.max(stand_pat);
+
+    let mut best = stand_pat;
+    for a_move in loud_moves(board) {
+        if context.timed_out() {
+            return Err(());
+        }
+        context.nodes_visited += 1;
+        let mut child_board = board.clone();
+        child_board.make_move_for_simulation(a_move.0, a_move.1, Some(&context.deadline)).map_err(|_| ())?;
+
+        let eval = -quiesce(&child_board, -beta, -alpha, depth_left - 1, context)?;
+        best = best.max(eval);
+        alpha = alpha.max(eval);
+        if context.pruning && alpha >= beta {
+            break;
+        }
+    }
+    Ok(best)
+}
+
+// The side to move's primed cells that border another player: playing one explodes straight into
+// enemy orbs.
+fn loud_moves(board: &Board) -> Vec<(usize, usize)> {
+    let mut moves = board.primed_cells(board.current_turn);
+    moves.retain(|&(row, col)| board.is_contested(row, col));
+    moves
+}
+
+// Cheap static ordering so alpha-beta sees likely-best moves first: cells one orb below critical
+// mass (they explode immediately) come first, then corners before edges before the interior.
+fn order_moves(board: &Board, mut moves: Vec<(usize, usize)>) -> Vec<(usize, usize)> {
+    moves.sort_by_cached_key(|&(row, col)| {
+        let cell = &board.cells[row][col];
+        (std::cmp::Reverse(cell.explodes_on_next_orb()), board.neighbors(row, col).len())
+    });
+    moves
+}
+
+// `order_moves` refined by what the search has learnt so far: exploding moves still come first, then
+// this depth's killer moves, then the remaining moves by history score.
+fn order_moves_with_context(board: &Board, mut moves: Vec<(usize, usize)>, depth: u32, context: &SearchContext) -> Vec<(usize, usize)> {
+    moves.sort_by_cached_key(|&(row, col)| {
+        let cell = &board.cells[row][col];
+        let history = context.history.get(&(row, col)).copied().unwrap_or(0);
+        (
+            std::cmp::Reverse(cell.explodes_on_next_orb()),
+            std::cmp::Reverse(context.is_killer(depth, (row, col))),
+            std::cmp::Reverse(history),
+            board.neighbors(row, col).len(),
+        )
+    });
+    moves
+}
+
+// --- Transposition table ---
+// Alpha-beta results keyed by Zobrist hash. Chain reactions often reach the same position through
+// different move orders, so a probe hit skips a whole subtree.
+const TT_MAX_ENTRIES: usize = 1 << 20;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Bound {
+    Exact,
+    // The true score is at least the stored one (the search failed high).
+    Lower,
+    // The true score is at most the stored one (the search failed low).
+    Upper,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct TTEntry {
+    depth: u32,
+    score: f64,
+    bound: Bound,
+}
+
+// What the stored scores were computed with. Scores are only reusable by a search that shares all of it;
+// the side to move is part of the hash, so one table serves both players.
+#[derive(Debug, Clone, PartialEq)]
+struct TTSignature {
+    dimensions: (u32, u32),
+    heuristics: Vec<Heuristic>,
+    options: EvalOptions,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct TranspositionTable {
+    entries: HashMap<u64, TTEntry>,
+    signature: Option<TTSignature>,
+}
+
+impl TranspositionTable {
+    pub fn clear(&mut self) {
+        *self = TranspositionTable::default();
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    // Drops every entry if they were scored by a different evaluator or on a different board shape.
+    fn prepare_for(&mut self, board: &Board, heuristics: &[Heuristic], options: EvalOptions) {
+        let signature = TTSignature {
+            dimensions: (board.width, board.height),
+            heuristics: heuristics.to_vec(),
+            options,
+        };
+        if self.signature.as_ref() != Some(&signature) {
+            self.entries.clear();
+            self.signature = Some(signature);
+        }
+    }
+
+    // A stored score that settles this node: exact, or a bound that already falls outside the window.
+    fn probe(&self, hash: u64, depth: u32, alpha: f64, beta: f64) -> Option<f64> {
+        let entry = self.entries.get(&hash)?;
+        if entry.depth < depth {
+            return None;
+        }
+        match entry.bound {
+            Bound::Exact => Some(entry.score),
+            Bound::Lower if entry.score >= beta => Some(entry.score),
+            Bound::Upper if entry.score <= alpha => Some(entry.score),
+            _ => None,
+        }
+    }
+
+    // Keeps the deeper of the old and new results for a position.
+    fn store(&mut self, hash: u64, depth: u32, score: f64, alpha: f64, beta: f64) {
+        if self.entries.get(&hash).is_some_and(|entry| entry.depth > depth) {
+            return;
+        }
+        if self.entries.len() >= TT_MAX_ENTRIES {
+            self.entries.clear();
+        }
+        let bound = if score <= alpha {
+            Bound::Upper
+        } else if score >= beta {
+            Bound::Lower
+        } else {
+            Bound::Exact
+        };
+        self.entries.insert(hash, TTEntry { depth, score, bound });
+    }
+}
+
+// --- Small-board solver ---
+// Exhaustive search to a proven win/loss. Only tractable on tiny boards, so larger ones are rejected.
+pub const SOLVER_MAX_CELLS: u32 = 9;
+const SOLVER_MAX_PLIES: u32 = 24;
+const SOLVER_TIME_LIMIT_MS: u64 = 10_000;
+// A win in k plies scores SOLVER_WIN - k, so faster wins (and slower losses) are preferred.
+const SOLVER_WIN: i32 = 1_000;
+
+// Search results that persist between calls for the current game. The Zobrist hash only covers
+// cell contents, so entries are only meaningful for the board dimensions they were computed on.
+#[derive(Default)]
+pub struct AICaches {
+    dimensions: Option<(u32, u32)>,
+    // Zobrist hash -> (plies searched, score for the side to move).
+    solver_memo: HashMap<u64, (u32, i32)>,
+    pub transpositions: TranspositionTable,
+}
+
+impl AICaches {
+    pub fn clear(&mut self) {
+        *self = AICaches::default();
+    }
+
+    // Drops every entry if the caches were filled for a board of a different shape.
+    fn prepare_for(&mut self, board: &Board) {
+        let dimensions = (board.width, board.height);
+        if self.dimensions != Some(dimensions) {
+            self.clear();
+            self.dimensions = Some(dimensions);
+        }
+    }
+}
+
+struct Solver<'a> {
+    memo: &'a mut HashMap<u64, (u32, i32)>,
+    deadline: Instant,
+}
+
+impl Solver<'_> {
+    // The score of playing `a_move` from `board`, from the mover's point of view.
+    fn score_move(&mut self, board: &Board, a_move: (usize, usize), depth: u32) -> Result<i32, ()> {
+        let mut child_board = board.clone();
+        child_board.make_move_for_simulation(a_move.0, a_move.1, Some(&self.deadline)).map_err(|_| ())?;
+        match child_board.game_state {
+            GameState::Won { .. } => return Ok(SOLVER_WIN - 1),
+            GameState::Draw => return Ok(0),
+            GameState::Ongoing => {}
+        }
+
+        // Shift the opponent's mate distance by one ply as it's passed back up.
+        let child_score = self.solve(&child_board, depth - 1)?;
+        Ok(if child_score > 0 { -child_score + 1 } else if child_score < 0 { -child_score - 1 } else { 0 })
+    }
+
+    // Negamax value of `board` for the side to move: positive is a forced win, negative a forced loss,
+    // zero means nothing was proven within `depth` plies.
+    fn solve(&mut self, board: &Board, depth: u32) -> Result<i32, ()> {
+        if Instant::now() >= self.deadline {
+            return Err(());
+        }
+        if depth == 0 {
+            return Ok(0);
+        }
+
+        let hash = board.zobrist_hash();
+        if let Some(&(searched, score)) = self.memo.get(&hash) {
+            // Proven results hold at any depth; unproven ones only if searched at least as deep.
+            if searched >= depth || score != 0 {
+                return Ok(score);
+            }
+        }
+
+        let mut best_score = -SOLVER_WIN;
+        for a_move in board.get_all_valid_moves() {
+            let score = self.score_move(board, a_move, depth)?;
+            best_score = best_score.max(score);
+            if best_score == SOLVER_WIN - 1 {
+                break;
+            }
+        }
+
+        self.memo.insert(hash, (depth, best_score));
+        Ok(best_score)
+    }
+}
+
+/// Solves a tiny board exactly and returns the line of optimal play (both sides) from the current
+/// position to the proven end of the game.
+pub fn solve_plan(board: &Board, caches: &mut AICaches) -> Result<Vec<(usize, usize)>, &'static str> {
+    if board.width * board.height > SOLVER_MAX_CELLS {
+        return Err("Board is too large to solve.");
+    }
+    if board.is_over() {
+        return Err("The game has already been won.");
+    }
+
+    caches.prepare_for(board);
+    let mut solver = Solver {
+        memo: &mut caches.solver_memo,
+        deadline: Instant::now() + Duration::from_millis(SOLVER_TIME_LIMIT_MS),
+    };
+    let root_score = solver.solve(board, SOLVER_MAX_PLIES).map_err(|_| "Solver timed out.")?;
+    if root_score == 0 {
+        return Err("No forced result found for this position.");
+    }
+
+    // Follow the moves that realise each node's solved value until the game ends.
+    let mut plan = Vec::new();
+    let mut current = board.clone();
+    let mut depth = SOLVER_MAX_PLIES;
+    let mut target = root_score;
+    while !current.is_over() && depth > 0 {
+        let mut chosen = None;
+        for a_move in current.get_all_valid_moves() {
+            let score = solver.score_move(&current, a_move, depth).map_err(|_| "Solver timed out.")?;
+            if score == target {
+                chosen = Some(a_move);
+                break;
+            }
+        }
+        let a_move = chosen.ok_or("Solver could not reconstruct the winning line.")?;
+
+        current.make_move_for_simulation(a_move.0, a_move.1, None).map_err(|_| "Solver produced an illegal move.")?;
+        plan.push(a_move);
+        // The opponent's value one ply later, undoing the mate-distance shift from score_move.
+        target = if target > 0 { -(target + 1) } else { -(target - 1) };
+        depth -= 1;
+    }
+    Ok(plan)
+}
+
+// How much more a primed front-line cell is worth to the side that gets to detonate first.
+const TEMPO_TO_MOVE_FACTOR: f64 = 1.5;
+
+/// Scores the "detonation race" from the side to move's point of view: primed cells bordering the
+/// enemy count for every side, but the mover's count extra because they get to explode first.
+/// Positive means the side to move is ahead of all its opponents together.
+pub fn tempo_score(board: &Board) -> f64 {
+    let player = board.current_turn;
+
+    let front_line = |p: Player| board.primed_cells(p).into_iter()
+        .filter(|&(r, c)| board.is_contested(r, c))
+        .count() as f64;
+
+    front_line(player) * TEMPO_TO_MOVE_FACTOR - board.opponents(player).map(front_line).sum::<f64>()
+}
+
+// Legal moves for the side to move, grouped by what the opponent can do in reply.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct MoveClassification {
+    // Ends the game immediately in the mover's favour.
+    pub winning: Vec<(usize, usize)>,
+    // No reply captures any of the mover's orbs.
+    pub safe: Vec<(usize, usize)>,
+    // Some reply captures orbs, but none eliminates the mover.
+    pub risky: Vec<(usize, usize)>,
+    // Some reply wins the game for the opponent.
+    pub losing: Vec<(usize, usize)>,
+}
+
+/// Sorts every legal move into winning/safe/risky/losing with one simulation per move and reply.
+pub fn classify_moves(board: &Board) -> MoveClassification {
+    let player = board.current_turn;
+    let mut classification = MoveClassification::default();
+
+    for a_move in board.get_all_valid_moves() {
+        let mut board_after_move = board.clone();
+        if board_after_move.make_move_for_simulation(a_move.0, a_move.1, None).is_err() { continue; }
+
+        if board_after_move.game_state == (GameState::Won { winner: player }) {
+            classification.winning.push(a_move);
+            continue;
+        }
+
+        let orbs_after_move = board_after_move.orb_counts[&player];
+        let mut captured = false;
+        let mut eliminated = false;
+        for reply in board_after_move.get_all_valid_moves() {
+            let mut board_after_reply = board_after_move.clone();
+            if board_after_reply.make_move_for_simulation(reply.0, reply.1, None).is_err() { continue; }
+
+            if let GameState::Won { winner } = board_after_reply.game_state {
+                if winner != player {
+                    eliminated = true;
+                    break;
+                }
+            }
+            if board_after_reply.orb_counts[&player] < orbs_after_move {
+                captured = true;
+            }
+        }
+
+        if eliminated {
+            classification.losing.push(a_move);
+        } else if captured {
+            classification.risky.push(a_move);
+        } else {
+            classification.safe.push(a_move);
+        }
+    }
+    classification
+}
+
+// Whether `attacker`, if it were their turn, has a move that wipes out all of `victim`'s orbs.
+fn can_eliminate(board: &Board, attacker: Player, victim: Player) -> bool {
+    if board.orb_counts[&victim] == 0 { return false; }
+
+    let mut attacker_view = board.clone();
+    attacker_view.current_turn = attacker;
+    attacker_view.get_all_valid_moves().into_iter().any(|a_move| {
+        let mut temp_board = attacker_view.clone();
+        temp_board.make_move_for_simulation(a_move.0, a_move.1, None).is_ok() && temp_board.orb_counts[&victim] == 0
+    })
+}
+
+/// A short coaching line per player: how many enemy cells stand between them and victory, and
+/// whether an opponent can eliminate them in a single move.
+pub fn win_requirements(board: &Board) -> HashMap<Player, String> {
+    let mut requirements = HashMap::new();
+    for player in board.players() {
+        let opponents: Vec<Player> = board.opponents(player).collect();
+
+        let mut requirement = if let GameState::Won { winner } = board.game_state {
+            if winner == player { "Has won the game.".to_string() } else { "Has been eliminated.".to_string() }
+        } else if board.is_eliminated(player) {
+            "Has been eliminated.".to_string()
+        } else {
+            let names: Vec<String> = opponents.iter().map(|opponent| format!("{:?}", opponent)).collect();
+            let enemy_cells: usize = opponents.iter().map(|&opponent| board.cell_count(opponent)).sum();
+            format!(
+                "Eliminate {}'s {} remaining cell(s); {:?} holds {} cell(s).",
+                names.join(" and "), enemy_cells, player, board.cell_count(player)
+            )
+        };
+        if !board.is_over() && !board.is_eliminated(player) {
+            if let Some(&opponent) = opponents.iter().find(|&&opponent| can_eliminate(board, opponent, player)) {
+                requirement.push_str(&format!(" In immediate danger: {:?} can eliminate {:?} next move.", opponent, player));
+            }
+        }
+        requirements.insert(player, requirement);
+    }
+    requirements
+}
+
+// Rough length of a game between evenly matched players, per board cell.
+const ESTIMATED_MOVES_PER_CELL: f64 = 2.0;
+
+/// A rough estimate of how many moves are left in the game, for UI pacing and time allocation.
+/// Emptier boards and closer orb counts both mean a longer game; it is not a prediction.
+pub fn estimate_remaining_moves(board: &Board) -> u32 {
+    if board.is_over() { return 0; }
+
+    let total_cells = (board.width * board.height) as f64;
+    let occupied = board.cells.iter().flatten()
+        .filter(|cell| cell.state != CellState::Empty)
+        .count() as f64;
+    let fill_ratio = occupied / total_cells;
+
+    let orbs: Vec<f64> = board.players()
+        .filter(|&player| !board.is_eliminated(player))
+        .map(|player| board.orb_counts[&player] as f64)
+        .collect();
+    let (fewest, most) = orbs.iter().fold((f64::INFINITY, 0.0_f64), |(fewest, most), &count| (fewest.min(count), most.max(count)));
+    // 1.0 for an even game, approaching 0.0 as one side runs out of orbs.
+    let balance = if most == 0.0 { 1.0 } else { fewest / most };
+
+    let estimate = total_cells * ESTIMATED_MOVES_PER_CELL * (1.0 - 0.5 * fill_ratio) * balance;
+    (estimate.round() as u32).max(1)
+}
+
+// Picks a uniformly random legal move for the side to move, or None if it has no moves.
+pub fn random_move<R: Rng>(board: &Board, rng: &mut R) -> Option<(usize, usize)> {
+    let possible_moves = board.get_all_valid_moves();
+    if possible_moves.is_empty() { return None; }
+    Some(possible_moves[rng.gen_range(0..possible_moves.len())])
+}
+
+// Upper bound on rollout length so a shuffling game can't spin forever.
+const ROLLOUT_MOVE_CAP: u32 = 1000;
+
+/// Plays uniformly random legal moves from `board` until the game ends or the move cap is hit,
+/// returning the final position. The same seed always produces the same rollout.
+pub fn random_rollout(board: &Board, seed: u64) -> Board {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut rollout_board = board.clone();
+
+    if rollout_board.is_over() { return rollout_board; }
+
+    for _ in 0..ROLLOUT_MOVE_CAP {
+        let Some((row, col)) = random_move(&rollout_board, &mut rng) else { break; };
+        if rollout_board.make_move_for_simulation(row, col, None) != Ok(GameState::Ongoing) { break; }
+    }
+    rollout_board
+}
+
+const W_ORB_DIFF: f64 = 1.0;
+const W_PERIPHERAL: f64 = 0.2;
+const W_TERRITORY: f64 = 0.1;
+const W_CHAIN_POTENTIAL: f64 = 0.5;
+const W_CONVERSION: f64 = 0.8;
+const W_CASCADE: f64 = 0.7;
+const W_SAFE_MOBILITY: f64 = 0.4;
+const W_MOBILITY: f64 = 0.3;
+const W_INFLUENCE: f64 = 0.3;
+const W_VULNERABILITY: f64 = 0.5;
+const W_CENTER: f64 = 0.2;
+const W_NEAR_WIN: f64 = 8.0;
+
+// A player down to this many occupied cells or fewer counts as close to elimination.
+const NEAR_WIN_CELLS: usize = 3;
+
+fn evaluate_board(board: &Board, heuristics: &[Heuristic], options: EvalOptions, player_for_pov: Player) -> f64 {
+    let player = player_for_pov;
+
+    // Anyone else winning is a loss, however many players there are.
+    if let GameState::Won { winner } = board.game_state {
+        return if winner == player { f64::INFINITY } else { f64::NEG_INFINITY };
+    }
+    if board.game_state == GameState::Draw { return 0.0; }
+
+    let mut total_score = near_win_term(board, player) * options.weights.near_win;
+    for heuristic in heuristics {
+        total_score += weighted_contribution(board, *heuristic, options, player);
+    }
+    total_score
+}
+
+// How close the other players are, on average, to being wiped out, minus how close `player` is, once
+// everyone has placed an orb. A player on their last cell counts NEAR_WIN_CELLS, down to 1 for one
+// holding exactly NEAR_WIN_CELLS cells, so the search presses an attack home instead of drifting. It
+// stays finite so a real win still outranks it, and averaging keeps it zero-sum across the players.
+fn near_win_term(board: &Board, player: Player) -> f64 {
+    if board.total_moves < board.player_count as u32 { return 0.0; }
+    let mut cells = [0; Player::ALL.len()];
+    for cell in board.cells.iter().flatten() {
+        if let CellState::Occupied { player, .. } = cell.state {
+            cells[player.index()] += 1;
+        }
+    }
+    let pressure = |count: usize| if count == 0 { 0.0 } else { (NEAR_WIN_CELLS + 1).saturating_sub(count) as f64 };
+    let others: f64 = Player::ALL[..board.player_count].iter()
+        .filter(|&&other| other != player)
+        .map(|&other| pressure(cells[other.index()]))
+        .sum();
+    others / (board.player_count - 1) as f64 - pressure(cells[player.index()])
+}
+
+// One heuristic's term in `evaluate_board`.
+fn weighted_contribution(board: &Board, heuristic: Heuristic, options: EvalOptions, player: Player) -> f64 {
+    evaluated_heuristic_value(board, heuristic, options, player) * options.weights.get(heuristic)
+}
+
+// One heuristic's unweighted value as `evaluate_board` sees it under `options`.
+fn evaluated_heuristic_value(board: &Board, heuristic: Heuristic, options: EvalOptions, player: Player) -> f64 {
+    if options.zero_sum {
+        zero_sum_heuristic_value(board, heuristic, player)
+    } else {
+        heuristic_value(board, heuristic, player)
+    }
+}
+
+/// The static evaluation of `board` from `player`'s point of view with default weights: positive
+/// favours `player`, and a won or lost game scores plus or minus infinity.
+pub fn score_board(board: &Board, heuristics: &[Heuristic], player: Player) -> f64 {
+    evaluate_board(board, heuristics, EvalOptions::default(), player)
+}
+
+// Cheap heuristics that mean the same thing for either side, used to score positions of a finished game.
+const TURNING_POINT_HEURISTICS: [Heuristic; 3] = [Heuristic::OrbDifference, Heuristic::ChainReactionPotential, Heuristic::ConversionPotential];
+
+/// Replays `moves` from `start` and returns the move number (counted like `total_moves`) after which
+/// the static evaluation swung furthest toward `winner`. The move that ends the game is skipped, since
+/// a won position scores infinite and would always be picked. None if no move helped the winner.
+pub fn find_turning_point(start: &Board, moves: &[(usize, usize)], winner: Player) -> Result<Option<u32>, MoveError> {
+    let options = EvalOptions::default();
+    let mut board = start.clone();
+    let mut previous = evaluate_board(&board, &TURNING_POINT_HEURISTICS, options, winner);
+    let mut turning_point = None;
+    let mut largest_swing = 0.0;
+
+    for &(row, col) in moves {
+        board.make_move_for_simulation(row, col, None)?;
+        if board.is_over() { break; }
+
+        let current = evaluate_board(&board, &TURNING_POINT_HEURISTICS, options, winner);
+        if current - previous > largest_swing {
+            largest_swing = current - previous;
+            turning_point = Some(board.total_moves);
+        }
+        previous = current;
+    }
+    Ok(turning_point)
+}
+
+// One move of a reviewed game. Scores are from the mover's point of view at the analysis depth, and
+// `delta` is how much worse the played move scored than the best one: 0 for the best move, growing
+// with the size of the blunder.
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct MoveAnalysis {
+    pub player: Player,
+    pub played: (usize, usize),
+    pub played_score: f64,
+    pub best: (usize, usize),
+    pub best_score: f64,
+    pub delta: f64,
+}
+
+/// Replays `moves` from `start` and searches each position `depth` plies deep, comparing the move that
+/// was played against the best one available. Fails on the first move that is out of turn or illegal.
+pub fn analyze_game(start: &Board, moves: &[(Player, usize, usize)], heuristics: &[Heuristic], depth: u32) -> Result<Vec<MoveAnalysis>, String> {
+    let mut board = start.clone();
+    let options = EvalOptions::default();
+    let depth = depth.max(1);
+    let mut table = TranspositionTable::default();
+    let mut analysis = Vec::with_capacity(moves.len());
+
+    for (index, &(player, row, col)) in moves.iter().enumerate() {
+        if player != board.current_turn {
+            return Err(format!("Move {} was played by {:?}, but it was {:?}'s turn", index, player, board.current_turn));
+        }
+        board.check_move(row, col).map_err(|e| format!("Move {} ({}, {}) is illegal: {}", index, row, col, e))?;
+
+        let deadline = Instant::now() + Duration::from_millis(ANALYSIS_TIME_LIMIT_MS);
+        let mut context = SearchContext::new(&mut table, heuristics, options, deadline, true);
+        let (mut best, mut best_score) = find_best_move_at_depth(&board, depth, None, (f64::NEG_INFINITY, f64::INFINITY), &mut context)
+            .ok_or_else(|| format!("Analysis of move {} timed out", index))?;
+        let played_score = score_root_move(&board, (row, col), depth, &mut context)
+            .map_err(|_| format!("Analysis of move {} timed out", index))?;
+        // The root search skips mirror images of moves early on, so the played move can tie or beat it.
+        if played_score >= best_score {
+            best = (row, col);
+            best_score = played_score;
+        }
+        // Two equal infinite scores would otherwise give NaN.
+        let delta = if best_score == played_score { 0.0 } else { best_score - played_score };
+        analysis.push(MoveAnalysis { player, played: (row, col), played_score, best, best_score, delta });
+
+        board.make_move_for_simulation(row, col, None).map_err(|e| format!("Move {} ({}, {}) is illegal: {}", index, row, col, e))?;
+    }
+    Ok(analysis)
+}
+
+// The static evaluation of the position after `a_move`, from `player_for_pov`'s point of view.
+pub fn evaluate_after_move(board: &Board, a_move: (usize, usize), heuristics: &[Heuristic], options: EvalOptions, player_for_pov: Player) -> Result<f64, MoveError> {
+    let mut board_after_move = board.clone();
+    board_after_move.make_move_for_simulation(a_move.0, a_move.1, None)?;
+    Ok(evaluate_board(&board_after_move, heuristics, options, player_for_pov))
+}
+
+/// The terms of `evaluate_board`'s score for `player`, each as a raw value and that value weighted.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EvalBreakdown {
+    /// One entry per heuristic, in the order given.
+    pub heuristics: Vec<(Heuristic, f64, f64)>,
+    /// The bonus for having other players down to their last few cells; 0 until then.
+    pub near_win: (f64, f64),
+}
+
+impl EvalBreakdown {
+    /// The weighted terms added up the way `evaluate_board` adds them, so for a position that isn't
+    /// decided yet this is exactly its score. A decided game scores infinite (or 0 for a draw)
+    /// whatever the terms say.
+    pub fn total(&self) -> f64 {
+        self.heuristics.iter().fold(self.near_win.1, |total, (_, _, weighted)| total + weighted)
+    }
+}
+
+/// Splits `evaluate_board(board, heuristics, options, player)` into its terms, e.g. to see which
+/// heuristic dominates while tuning weights.
+pub fn evaluate_breakdown(board: &Board, heuristics: &[Heuristic], options: EvalOptions, player: Player) -> EvalBreakdown {
+    let near_win = near_win_term(board, player);
+    EvalBreakdown {
+        heuristics: heuristics.iter().map(|&heuristic| {
+            let raw = evaluated_heuristic_value(board, heuristic, options, player);
+            (heuristic, raw, raw * options.weights.get(heuristic))
+        }).collect(),
+        near_win: (near_win, near_win * options.weights.near_win),
+    }
+}
+
+// Every heuristic except SafeMobility and Vulnerability is already "mine minus the opponent's".
+// SafeMobility only counts the side to move's safe moves and Vulnerability only `player`'s exposed
+// stacks, so on their own they aren't anti-symmetric.
+// This forces the form (mine - theirs) / 2, with theirs averaged over the opponents, which leaves
+// anti-symmetric heuristics unchanged in a two-player game and makes every player's evaluations add
+// up to zero (eval(pos, me) == -eval(pos, opponent) with two players).
+fn zero_sum_heuristic_value(board: &Board, heuristic: Heuristic, player: Player) -> f64 {
+    let theirs: Vec<f64> = board.opponents(player).map(|opponent| heuristic_value_for_side(board, heuristic, opponent)).collect();
+    let theirs = if theirs.is_empty() { 0.0 } else { theirs.iter().sum::<f64>() / theirs.len() as f64 };
+    (heuristic_value_for_side(board, heuristic, player) - theirs) / 2.0
+}
+
+// Like heuristic_value, but move-based heuristics look at `player`'s moves even when it isn't their turn.
+fn heuristic_value_for_side(board: &Board, heuristic: Heuristic, player: Player) -> f64 {
+    if heuristic == Heuristic::SafeMobility && board.current_turn != player {
+        let mut side_view = board.clone();
+        side_view.current_turn = player;
+        return heuristic_value(&side_view, heuristic, player);
+    }
+    heuristic_value(board, heuristic, player)
+}
+
+// The unweighted value of a single heuristic from `player`'s point of view. Every other player still
+// in the game counts as an opponent.
+fn heuristic_value(board: &Board, heuristic: Heuristic, player: Player) -> f64 {
+    match heuristic {
+        Heuristic::OrbDifference => {
+            let my_orbs = board.orb_counts[&player] as f64;
+            let opponent_orbs: f64 = board.opponents(player).map(|opponent| board.orb_counts[&opponent] as f64).sum();
+            my_orbs - opponent_orbs
+        }
+        Heuristic::PeripheralControl => {
+            let mut peripheral_score = 0.0;
+            for r in 0..board.height as usize{
+                for c in 0..board.width as usize{
+                    if let CellState::Occupied { player: cell_player, .. } = board.cells[r][c].state {
+                        let is_corner = (r == 0 || r == board.height as usize - 1) && (c == 0 || c == board.width as usize - 1);
+                        let is_edge = r == 0 || r == board.height as usize - 1 || c == 0 || c == board.width as usize - 1;
+                        let value = if is_corner { 3.0 } else if is_edge { 2.0 } else { 1.0 };
+                        if cell_player == player {
+                            peripheral_score += value;
+                        } else {
+                            peripheral_score -= value;
+                        }
+                    }
+                }
+            }
+            peripheral_score
+        }
+        Heuristic::TerritoryControl => {
+            let mut territory_score = 0.0;
+            for r in 0..board.height as usize {
+                for c in 0..board.width as usize {
+                    if let CellState::Occupied { player: cell_player, .. } = board.cells[r][c].state {
+                        if cell_player == player {
+                            territory_score += 1.0;
+                        } else {
+                            territory_score -= 1.0;
+                        }
+                    }
+                }
+            }
+            territory_score
+        }
+        Heuristic::ChainReactionPotential => {
+            let mut chain_reaction_score = 0.0;
+            for r in 0..board.height as usize {
+                for c in 0..board.width as usize {
+                    if let CellState::Occupied { player: cell_player, orbs } = board.cells[r][c].state {
+                        if orbs == board.cells[r][c].critical_mass - 1 {
+                            if cell_player == player {
+                                chain_reaction_score += 5.0; 
+                            } else {
+                                chain_reaction_score -= 5.0; 
+                            }
+                        }
+                    } 
+                }
+            }
+            chain_reaction_score
+        }
+        // --- REVISED HEURISTIC LOGIC ---
+        Heuristic::ConversionPotential => {
+            let mut conversion_score = 0.0;
+
+            for r in 0..board.height as usize {
+                for c in 0..board.width as usize {
+                    if let CellState::Occupied { player: cell_player, orbs } = board.cells[r][c].state {
+                        // Calculate how many orbs are needed for this cell to explode. A cascade cut off
+                        // by the explosion cap can leave a cell at or over critical mass.
+                        let orbs_to_explode = board.cells[r][c].critical_mass.saturating_sub(orbs) as f64;
+
+                        // Only consider cells that are not yet at critical mass.
+                        if orbs_to_explode > 0.0 {
+                            let mut opponent_neighbors = 0;
+                            for (nr, nc) in board.neighbors(r, c) {
+                                if let CellState::Occupied { player: neighbor_player, .. } = board.cells[nr][nc].state {
+                                    // Count how many adjacent cells belong to the opponent.
+                                    if neighbor_player != cell_player {
+                                        opponent_neighbors += 1;
+                                    }
+                                }
+                            }
+                            
+                            if opponent_neighbors > 0 {
+                                // The potential is the number of opponent cells that would be captured,
+                                // weighted by how close the cell is to exploding.
+                                // A smaller 'orbs_to_explode' value leads to a higher potential score.
+                                let potential = opponent_neighbors as f64 / orbs_to_explode;
+
+                                if cell_player == player {
+                                    conversion_score += potential;
+                                } else {
+                                    conversion_score -= potential;
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+            conversion_score
+        }
+        Heuristic::SafeMobility => {
+            let mut my_safe_moves = 0.0;
+            let my_possible_moves = board.get_all_valid_moves();
+            for my_move in &my_possible_moves {
+                let mut board_after_my_move = board.clone();
+                // FIX: Pass None for the deadline, as this sub-simulation is not time-critical on its own.
+                if board_after_my_move.make_move_for_simulation(my_move.0, my_move.1, None).is_err() {
+                    continue;
+                }
+                // A move that ends the game leaves no one to reply.
+                if board_after_my_move.is_over() {
+                    my_safe_moves += 1.0;
+                    continue;
+                }
+                let mut is_move_safe = true;
+                let opponent_replies = board_after_my_move.get_all_valid_moves();
+                // Unsafe if any reply the next player could make sets off an explosion.
+                for opp_reply in &opponent_replies {
+                    if board_after_my_move.cells[opp_reply.0][opp_reply.1].explodes_on_next_orb() {
+                         is_move_safe = false;
+                         break;
+                    }
+                }
+                if is_move_safe {
+                    my_safe_moves += 1.0;
+                }
+            }
+            my_safe_moves
+        }
+        Heuristic::CascadePotential => {
+            let mut cascade_score = 0.0;
+            for r in 0..board.height as usize {
+                for c in 0..board.width as usize {
+                    if let CellState::Occupied { player: trigger_player, orbs } = board.cells[r][c].state {
+                        if orbs == board.cells[r][c].critical_mass - 1 {
+                            let mut current_cascade_value = 0.0;
+                            for (nr, nc) in board.neighbors(r, c) {
+                                if let CellState::Occupied { orbs: neighbor_orbs, .. } = board.cells[nr][nc].state {
+                                    current_cascade_value += neighbor_orbs as f64;
+                                    if neighbor_orbs == board.cells[nr][nc].critical_mass - 1 {
+                                        current_cascade_value += 5.0;
+                                    }
+                                }
+                            }
+                            if trigger_player == player {
+                                cascade_score += current_cascade_value;
+                            } else {
+                                cascade_score -= current_cascade_value;
+                            }
+                        }
+                    }
+                }
+            }
+            cascade_score
+        }
+        Heuristic::Mobility => {
+            // Moves each side would have if it were their turn.
+            let moves_for = |side: Player| {
+                let mut side_view = board.clone();
+                side_view.current_turn = side;
+                side_view.get_all_valid_moves().len() as f64
+            };
+            moves_for(player) - board.opponents(player).map(moves_for).sum::<f64>()
+        }
+        Heuristic::Influence => {
+            // Each empty cell goes to whichever side's orbs it is fewer steps from, every opponent's
+            // orbs counting as one side; ties go to no one.
+            let mine = steps_from(board, |owner| owner == player);
+            let theirs = steps_from(board, |owner| owner != player);
+            let mut influence_score = 0.0;
+            for (index, cell) in board.cells.iter().flatten().enumerate() {
+                if cell.state != CellState::Empty { continue; }
+                match (mine[index], theirs[index]) {
+                    (Some(m), Some(t)) if m < t => influence_score += 1.0,
+                    (Some(m), Some(t)) if m > t => influence_score -= 1.0,
+                    (Some(_), None) => influence_score += 1.0,
+                    (None, Some(_)) => influence_score -= 1.0,
+                    _ => {}
+                }
+            }
+            influence_score
+        }
+        Heuristic::Vulnerability => {
+            let mut exposed_orbs = 0.0;
+            for r in 0..board.height as usize {
+                for c in 0..board.width as usize {
+                    let CellState::Occupied { player: cell_player, orbs } = board.cells[r][c].state else { continue; };
+                    if cell_player != player || !board.cells[r][c].explodes_on_next_orb() { continue; }
+                    // One orb on a primed opponent neighbour explodes into this cell and takes the whole stack.
+                    let threatened = board.neighbors(r, c).into_iter().any(|(nr, nc)| {
+                        matches!(board.cells[nr][nc].state, CellState::Occupied { player: neighbor_player, .. } if neighbor_player != player)
+                            && board.cells[nr][nc].explodes_on_next_orb()
+                    });
+                    if threatened {
+                        exposed_orbs += orbs as f64;
+                    }
+                }
+            }
+            -exposed_orbs
+        }
+        Heuristic::CenterControl => {
+            let center_r = (board.height as f64 - 1.0) / 2.0;
+            let center_c = (board.width as f64 - 1.0) / 2.0;
+            let mut center_score = 0.0;
+            for r in 0..board.height as usize {
+                for c in 0..board.width as usize {
+                    if let CellState::Occupied { player: cell_player, .. } = board.cells[r][c].state {
+                        let distance = (r as f64 - center_r).abs() + (c as f64 - center_c).abs();
+                        let value = 1.0 / (1.0 + distance);
+                        if cell_player == player {
+                            center_score += value;
+                        } else {
+                            center_score -= value;
+                        }
+                    }
+                }
+            }
+            center_score
+        }
+    }
+}
+
+// Breadth-first distance from the nearest cell whose owner passes `owned` to every cell, indexed
+// row-major. None everywhere if no such cell exists.
+fn steps_from(board: &Board, owned: impl Fn(Player) -> bool) -> Vec<Option<u32>> {
+    let width = board.width as usize;
+    let mut steps = vec![None; width * board.height as usize];
+    let mut frontier = VecDeque::new();
+    for (index, cell) in board.cells.iter().flatten().enumerate() {
+        if matches!(cell.state, CellState::Occupied { player: owner, .. } if owned(owner)) {
+            steps[index] = Some(0);
+            frontier.push_back((index / width, index % width));
+        }
+    }
+    while let Some((row, col)) = frontier.pop_front() {
+        let next = steps[row * width + col].map(|s| s + 1);
+        for (nr, nc) in board.neighbors(row, col) {
+            if steps[nr * width + nc].is_none() {
+                steps[nr * width + nc] = next;
+                frontier.push_back((nr, nc));
+            }
+        }
+    }
+    steps
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::CriticalMassRule;
+
+    const FULL_WINDOW: (f64, f64) = (f64::NEG_INFINITY, f64::INFINITY);
+    // Cheap enough to search a few plies deep in a debug build.
+    const TEST_HEURISTICS: [Heuristic; 5] = [
+        Heuristic::OrbDifference, Heuristic::PeripheralControl, Heuristic::ChainReactionPotential,
+        Heuristic::ConversionPotential, Heuristic::CascadePotential,
+    ];
+
+    fn empty_board(width: u32, height: u32) -> Board {
+        Board::new(width, height, Player::Red, 2, CriticalMassRule::NeighbourCount, Topology::Grid)
+    }
+
+    // A 6x9 game `plies` random moves in, with real choices left to make.
+    fn midgame_board(seed: u64, plies: u32) -> Board {
+        let mut board = empty_board(6, 9);
+        let mut rng = StdRng::seed_from_u64(seed);
+        for _ in 0..plies {
+            let (row, col) = random_move(&board, &mut rng).unwrap();
+            board.make_move_for_simulation(row, col, None).unwrap();
+        }
+        assert!(!board.is_over());
+        board
+    }
+
+    // A pruning search over TEST_HEURISTICS that won't run out of time.
+    fn test_context(table: &mut TranspositionTable) -> SearchContext<'_> {
+        SearchContext::new(table, &TEST_HEURISTICS, EvalOptions::default(), Instant::now() + Duration::from_secs(600), true)
+    }
+
+    #[test]
+    fn each_tie_break_policy_picks_its_move() {
+        let board = empty_board(3, 3);
+        let tied = [(0, 1), (1, 1), (0, 0)];
+        let center = [Heuristic::CenterControl];
+        let options = EvalOptions::default();
+
+        assert_eq!(break_tie(&board, &center, options, &tied, TieBreak::First), (0, 1));
+        assert_eq!(break_tie(&board, &center, options, &tied, TieBreak::NearCorner), (0, 0));
+        // The centre cell is the one CenterControl likes best one ply on.
+        assert_eq!(break_tie(&board, &center, options, &tied, TieBreak::Shallow), (1, 1));
+
+        let picks: Vec<_> = (0..20).map(|seed| break_tie(&board, &center, options, &tied, TieBreak::Random(seed))).collect();
+        assert!(picks.iter().all(|pick| tied.contains(pick)));
+        assert!(picks.iter().any(|&pick| pick != picks[0]), "every seed picked {:?}", picks[0]);
+        for seed in 0..20 {
+            assert_eq!(break_tie(&board, &center, options, &tied, TieBreak::Random(seed)), picks[seed as usize]);
+        }
+    }
+
+    #[test]
+    fn search_settles_ties_with_its_policy() {
+        // With no heuristics every move on an empty board scores the same.
+        let board = empty_board(5, 5);
+        let search = |tie_break| {
+            let params = SearchParams { max_depth: 1, time_limit_ms: 10_000, pruning: true, tie_break };
+            search_move(&board, &[], EvalOptions::default(), params, &mut TranspositionTable::default()).unwrap().chosen
+        };
+
+        assert_eq!(search(TieBreak::NearCorner), (0, 0));
+        assert_eq!(search(TieBreak::Random(7)), search(TieBreak::Random(7)));
+        let canonical = board.canonical_moves();
+        assert!((0..10).all(|seed| canonical.contains(&search(TieBreak::Random(seed)))));
+    }
+
+    #[test]
+    fn aspiration_window_finds_the_same_move_with_fewer_nodes() {
+        // At 0xC4A1_0002 the score climbs by more than ASPIRATION_DELTA, so the first window fails high
+        // and is searched again.
+        let mut failed_windows = 0;
+        for (seed, plies) in [(0xC4A1_5EED, 30), (0xC4A1_0002, 26), (0xC4A1_0003, 34), (0xC4A1_0004, 24), (0xC4A1_0006, 28), (0xC4A1_0007, 22)] {
+            let board = midgame_board(seed, plies);
+            let depth = 3;
+            let mut table = TranspositionTable::default();
+            let (pv_move, previous_score) = find_best_move_at_depth(&board, depth - 1, None, FULL_WINDOW, &mut test_context(&mut table)).unwrap();
+
+            let mut full_table = TranspositionTable::default();
+            let mut full_context = test_context(&mut full_table);
+            let full = find_best_move_at_depth(&board, depth, Some(pv_move), FULL_WINDOW, &mut full_context).unwrap();
+
+            let mut aspiration_table = TranspositionTable::default();
+            let mut aspiration_context = test_context(&mut aspiration_table);
+            let aspirated = aspiration_search(&board, depth, Some(pv_move), previous_score, &mut aspiration_context).unwrap();
+
+            assert_eq!(aspirated, full, "seed {:#x}", seed);
+            assert!(aspiration_context.nodes_visited < full_context.nodes_visited,
+                "seed {:#x}: aspiration {} nodes, full window {}", seed, aspiration_context.nodes_visited, full_context.nodes_visited);
+            if (full.1 - previous_score).abs() >= ASPIRATION_DELTA {
+                failed_windows += 1;
+            }
+        }
+        assert_eq!(failed_windows, 1);
+    }
+
+    #[test]
+    fn quiescence_sees_the_refutation_a_one_ply_search_misses() {
+        // Red's corner at (0, 2) explodes and captures (0, 1), the best score one ply on, but it leaves
+        // Blue a chain of captures straight back.
+        let board = Board::from_ascii(&["1B 1B 1R", "1B 1R 1R", "0 2R 0"]).unwrap();
+        let orbs = [Heuristic::OrbDifference];
+        let options = EvalOptions::default();
+        let blunder = (0, 2);
+        let static_score = |a_move| evaluate_after_move(&board, a_move, &orbs, options, Player::Red).unwrap();
+        for a_move in board.get_all_valid_moves() {
+            if a_move != blunder {
+                assert!(static_score(blunder) > static_score(a_move), "{:?} scores as well as the blunder", a_move);
+            }
+        }
+
+        let mut table = TranspositionTable::default();
+        let mut context = SearchContext::new(&mut table, &orbs, options, Instant::now() + Duration::from_secs(600), true);
+        let (chosen, score) = find_best_move_at_depth(&board, 1, None, FULL_WINDOW, &mut context).unwrap();
+        assert_ne!(chosen, blunder);
+        assert!(score_root_move(&board, blunder, 1, &mut context).unwrap() < score);
+    }
+
+    #[test]
+    fn depth_zero_still_picks_a_legal_move() {
+        let board = midgame_board(0xDE_0000, 20);
+        for strategy in [AIStrategy::AlphaBeta, AIStrategy::Minimax] {
+            let stats = get_ai_move(&board, strategy, &TEST_HEURISTICS, EvalOptions::default(), 0, 60_000).unwrap();
+            assert!(board.get_all_valid_moves().contains(&stats.chosen), "{:?} picked {:?}", strategy, stats.chosen);
+            assert_eq!(stats.depth_reached, 1);
+        }
+    }
+
+    #[test]
+    fn killers_and_history_visit_fewer_nodes_than_static_ordering() {
+        let board = midgame_board(0xC4A1_5EED, 30);
+        let depth = 4;
+
+        let mut learnt_table = TranspositionTable::default();
+        let mut learnt_context = test_context(&mut learnt_table);
+        let learnt = find_best_move_at_depth(&board, depth, None, FULL_WINDOW, &mut learnt_context).unwrap();
+
+        let mut static_table = TranspositionTable::default();
+        let mut static_context = test_context(&mut static_table);
+        static_context.learn_ordering = false;
+        let static_only = find_best_move_at_depth(&board, depth, None, FULL_WINDOW, &mut static_context).unwrap();
+
+        assert_eq!(learnt.1, static_only.1);
+        assert!(learnt_context.nodes_visited < static_context.nodes_visited,
+            "killers and history {} nodes, static ordering {}", learnt_context.nodes_visited, static_context.nodes_visited);
+    }
+
+    #[test]
+    fn searching_the_previous_best_move_first_visits_fewer_nodes() {
+        let board = midgame_board(0xC4A1_5EED, 30);
+        let depth = 3;
+        let mut table = TranspositionTable::default();
+        let (pv_move, _) = find_best_move_at_depth(&board, depth - 1, None, FULL_WINDOW, &mut test_context(&mut table)).unwrap();
+
+        let mut cold_table = TranspositionTable::default();
+        let mut cold_context = test_context(&mut cold_table);
+        let cold = find_best_move_at_depth(&board, depth, None, FULL_WINDOW, &mut cold_context).unwrap();
+
+        let mut pv_table = TranspositionTable::default();
+        let mut pv_context = test_context(&mut pv_table);
+        let with_pv = find_best_move_at_depth(&board, depth, Some(pv_move), FULL_WINDOW, &mut pv_context).unwrap();
+
+        assert_eq!(with_pv.1, cold.1);
+        assert!(pv_context.nodes_visited < cold_context.nodes_visited,
+            "previous best move first {} nodes, static order {}", pv_context.nodes_visited, cold_context.nodes_visited);
+    }
+
+    #[test]
+    fn analysis_flags_the_blunder_and_not_the_best_move() {
+        // The same trap as the quiescence test: (0, 2) captures a cell but hands Blue the game back.
+        let start = Board::from_ascii(&["1B 1B 1R", "1B 1R 1R", "0 2R 0"]).unwrap();
+        let orbs = [Heuristic::OrbDifference];
+        let blunder = analyze_game(&start, &[(Player::Red, 0, 2)], &orbs, 2).unwrap();
+        assert_eq!(blunder.len(), 1);
+        assert!(blunder[0].delta > 0.0, "{:?}", blunder[0]);
+        assert_ne!(blunder[0].best, (0, 2));
+
+        let best = blunder[0].best;
+        let replayed = analyze_game(&start, &[(Player::Red, best.0, best.1)], &orbs, 2).unwrap();
+        assert_eq!(replayed[0].delta, 0.0);
+
+        assert!(analyze_game(&start, &[(Player::Blue, 2, 0)], &orbs, 2).is_err());
+    }
+
+    #[test]
+    fn breakdown_adds_up_to_the_evaluation() {
+        let board = midgame_board(0xB4EA_4D0E, 24);
+        let weights = HeuristicWeights { orb_difference: 2.5, cascade_potential: 0.1, ..HeuristicWeights::default() };
+        for options in [EvalOptions::default(), EvalOptions { zero_sum: true, weights }] {
+            for player in [Player::Red, Player::Blue] {
+                let breakdown = evaluate_breakdown(&board, &Heuristic::ALL, options, player);
+                assert_eq!(breakdown.heuristics.len(), Heuristic::ALL.len());
+                assert_eq!(breakdown.total(), evaluate_board(&board, &Heuristic::ALL, options, player));
+            }
+        }
+
+        // Blue is down to one cell, so the near-win bonus counts towards Red's score.
+        let mut nearly_won = Board::from_ascii(&["1R 2R 0", "1R 0 0", "0 0 1B"]).unwrap();
+        nearly_won.total_moves = 6;
+        let breakdown = evaluate_breakdown(&nearly_won, &TEST_HEURISTICS, EvalOptions::default(), Player::Red);
+        assert!(breakdown.near_win.1 > 0.0);
+        assert_eq!(breakdown.total(), evaluate_board(&nearly_won, &TEST_HEURISTICS, EvalOptions::default(), Player::Red));
+    }
+
+    #[test]
+    fn three_player_games_score_every_rival_as_an_opponent() {
+        let mut board = Board::from_ascii(&["1R 0 1B", "0 2G 0", "0 0 1B"]).unwrap();
+        board.total_moves = 3;
+        assert_eq!(board.opponents(Player::Red).collect::<Vec<_>>(), [Player::Blue, Player::Green]);
+        assert_eq!(heuristic_value(&board, Heuristic::OrbDifference, Player::Red), 1.0 - 4.0);
+
+        // Zero-sum scores over all three players cancel out.
+        let options = EvalOptions { zero_sum: true, ..EvalOptions::default() };
+        let total: f64 = board.players().map(|player| evaluate_board(&board, &TEST_HEURISTICS, options, player)).sum();
+        assert!(total.abs() < 1e-9, "zero-sum evaluations add up to {}", total);
+
+        let requirements = win_requirements(&board);
+        assert_eq!(requirements.len(), 3);
+        assert!(requirements[&Player::Red].starts_with("Eliminate Blue and Green's 3 remaining cell(s)"), "{}", requirements[&Player::Red]);
+
+        // Blue winning is a loss for Green just as much as for Red.
+        board.game_state = GameState::Won { winner: Player::Blue };
+        assert_eq!(score_board(&board, &TEST_HEURISTICS, Player::Green), f64::NEG_INFINITY);
+        assert_eq!(score_board(&board, &TEST_HEURISTICS, Player::Blue), f64::INFINITY);
+        assert_ne!(side_to_move(&board), Player::Blue);
+    }
+
+    #[test]
+    fn alpha_beta_plays_a_four_player_game_to_the_end() {
+        let mut board = Board::new(4, 4, Player::Red, 4, CriticalMassRule::NeighbourCount, Topology::Grid);
+        for _ in 0..500 {
+            if board.is_over() { break; }
+            let stats = get_ai_move(&board, AIStrategy::AlphaBeta, &TEST_HEURISTICS, EvalOptions::default(), 2, 60_000).unwrap();
+            board.make_move_for_simulation(stats.chosen.0, stats.chosen.1, None).unwrap();
+        }
+        assert!(board.winner().is_some(), "no winner after {} moves", board.total_moves);
+    }
+
+    #[test]
+    fn zeroing_the_dominant_heuristic_moves_the_score_most() {
+        // Orb difference carries five times the weight of peripheral control and is never small in
+        // the middle game, so it should be the one the decision hinges on.
+        let board = midgame_board(0x5E45_0001, 16);
+        let heuristics = [Heuristic::OrbDifference, Heuristic::PeripheralControl];
+        let sensitivity = heuristic_sensitivity(&board, &heuristics, EvalOptions::default()).unwrap();
+
+        assert_eq!(sensitivity.iter().map(|&(heuristic, _)| heuristic).collect::<Vec<_>>(), heuristics);
+        assert!(sensitivity[0].1.abs() > sensitivity[1].1.abs(), "{:?}", sensitivity);
+
+        // Red has nowhere to play on a board Blue fills.
+        let no_moves = Board::from_ascii(&["1B 1B", "1B 1B"]).unwrap();
+        assert!(heuristic_sensitivity(&no_moves, &heuristics, EvalOptions::default()).is_err());
+    }
+
+    #[test]
+    fn seeded_rollouts_repeat_and_run_to_the_end() {
+        let start = midgame_board(0x0011_0E47, 10);
+        for seed in 0..8 {
+            let rollout = random_rollout(&start, seed);
+            assert_eq!(rollout.to_log_text(), random_rollout(&start, seed).to_log_text());
+            assert!(rollout.is_over() || rollout.total_moves - start.total_moves >= ROLLOUT_MOVE_CAP,
+                "seed {} stopped after {} moves", seed, rollout.total_moves - start.total_moves);
+        }
+        assert_ne!(random_rollout(&start, 1).to_log_text(), random_rollout(&start, 2).to_log_text());
+    }
+
+    #[test]
+    fn a_solved_plan_plays_out_to_the_win() {
+        let mut board = empty_board(3, 3);
+        board.apply_moves(&[(0, 0), (2, 2), (0, 2), (2, 0)]).unwrap();
+        let plan = solve_plan(&board, &mut AICaches::default()).unwrap();
+
+        let mut played = board.clone();
+        for (i, &(row, col)) in plan.iter().enumerate() {
+            assert!(!played.is_over(), "the game ended before move {} of the plan", i);
+            played.make_move_for_simulation(row, col, None).unwrap();
+        }
+        // Red is to move and has the forced win.
+        assert_eq!(played.winner(), Some(Player::Red), "{:?} did not win the game", plan);
+
+        assert_eq!(solve_plan(&empty_board(4, 4), &mut AICaches::default()), Err("Board is too large to solve."));
+    }
+
+    #[test]
+    fn a_lopsided_game_has_fewer_moves_left_than_an_even_opening() {
+        let mut opening = empty_board(6, 9);
+        opening.apply_moves(&[(0, 0), (8, 5)]).unwrap();
+
+        let mut lopsided = Board::from_ascii(&[
+            "2R 2R 1R 0 0 0", "2R 3R 2R 0 0 0", "1R 2R 0 0 0 0", "0 0 0 0 0 0", "0 0 0 0 0 0",
+            "0 0 0 0 0 0", "0 0 0 0 0 0", "0 0 0 0 0 0", "0 0 0 0 0 1B",
+        ]).unwrap();
+        lopsided.total_moves = 20;
+
+        assert!(estimate_remaining_moves(&lopsided) < estimate_remaining_moves(&opening),
+            "lopsided {}, opening {}", estimate_remaining_moves(&lopsided), estimate_remaining_moves(&opening));
+        lopsided.game_state = GameState::Won { winner: Player::Red };
+        assert_eq!(estimate_remaining_moves(&lopsided), 0);
+    }
+
+    #[test]
+    fn caches_from_another_board_size_are_not_reused() {
+        let mut caches = AICaches::default();
+        let mut three_by_three = empty_board(3, 3);
+        three_by_three.apply_moves(&[(0, 0), (2, 2), (0, 2), (2, 0)]).unwrap();
+        solve_plan(&three_by_three, &mut caches).unwrap();
+        let big = midgame_board(0xCAC4_E001, 20);
+        alphabeta_move(&big, &TEST_HEURISTICS, EvalOptions::default(), 2, 60_000, TieBreak::First, &mut caches.transpositions).unwrap();
+        assert!(!caches.transpositions.is_empty());
+
+        // A new game on a smaller board gets the same answers as it would from empty caches.
+        let mut small = empty_board(2, 3);
+        small.apply_moves(&[(0, 0), (2, 1)]).unwrap();
+        assert_eq!(solve_plan(&small, &mut caches), solve_plan(&small, &mut AICaches::default()));
+        let reused = alphabeta_move(&small, &TEST_HEURISTICS, EvalOptions::default(), 2, 60_000, TieBreak::First, &mut caches.transpositions).unwrap();
+        let fresh = alphabeta_move(&small, &TEST_HEURISTICS, EvalOptions::default(), 2, 60_000, TieBreak::First, &mut TranspositionTable::default()).unwrap();
+        assert_eq!((reused.chosen, reused.score), (fresh.chosen, fresh.score));
+    }
+
+    #[test]
+    fn berserk_sets_off_the_longest_cascade() {
+        // Any of the primed cells along the top row sets off all three; the lone corner only itself.
+        let mut board = Board::from_ascii(&[
+            "1R 2R 2R 0 0", "0 0 0 0 0", "0 0 0 0 0", "0 0 0 0 0", "1R 0 0 0 1B",
+        ]).unwrap();
+        board.total_moves = 4;
+        let longest = board.get_all_valid_moves().into_iter().map(|(row, col)| board.cascade_length(row, col).unwrap()).max();
+        assert_eq!(longest, Some(3));
+
+        let chosen = get_ai_move(&board, AIStrategy::Berserk, &[], EvalOptions::default(), 1, 1000).unwrap().chosen;
+        assert!([(0, 0), (0, 1), (0, 2)].contains(&chosen), "picked {:?}", chosen);
+
+        // Both corners explode once, but only the bottom one takes a Blue cell.
+        let mut tied = Board::from_ascii(&["1R 0 1B", "0 0 0", "1R 1B 0"]).unwrap();
+        tied.total_moves = 4;
+        assert_eq!(get_ai_move(&tied, AIStrategy::Berserk, &[], EvalOptions::default(), 1, 1000).unwrap().chosen, (2, 0));
+    }
+
+    #[test]
+    fn tempo_favours_whoever_moves_first_in_a_standoff() {
+        // Two primed corners face each other; whoever explodes first takes the other.
+        let mut standoff = Board::from_ascii(&["1R 1B", "0 0"]).unwrap();
+        standoff.total_moves = 2;
+        let red_to_move = tempo_score(&standoff);
+        standoff.current_turn = Player::Blue;
+        assert!(red_to_move > 0.0, "{}", red_to_move);
+        assert_eq!(tempo_score(&standoff), red_to_move);
+
+        // Diagonal corners are just as primed, but neither touches the other.
+        let calm = Board::from_ascii(&["1R 0", "0 1B"]).unwrap();
+        assert_eq!(tempo_score(&calm), 0.0);
+    }
+
+    #[test]
+    fn win_requirements_warn_the_player_about_to_be_eliminated() {
+        // Red's primed corner explodes into Blue's only cell.
+        let mut board = Board::from_ascii(&["1R 1B 0", "0 0 0", "0 0 0"]).unwrap();
+        board.total_moves = 2;
+        let requirements = win_requirements(&board);
+
+        assert_eq!(requirements[&Player::Red], "Eliminate Blue's 1 remaining cell(s); Red holds 1 cell(s).");
+        assert_eq!(requirements[&Player::Blue],
+            "Eliminate Red's 1 remaining cell(s); Blue holds 1 cell(s). In immediate danger: Red can eliminate Blue next move.");
+
+        board.make_move_for_simulation(0, 0, None).unwrap();
+        let requirements = win_requirements(&board);
+        assert_eq!(requirements[&Player::Red], "Has won the game.");
+        assert_eq!(requirements[&Player::Blue], "Has been eliminated.");
+    }
+
+    #[test]
+    fn zero_sum_heuristics_are_anti_symmetric() {
+        let options = EvalOptions { zero_sum: true, ..EvalOptions::default() };
+        for seed in 0..4 {
+            let board = midgame_board(0x2E40_0000 + seed, 18);
+            for heuristic in Heuristic::ALL {
+                let red = evaluate_board(&board, &[heuristic], options, Player::Red);
+                let blue = evaluate_board(&board, &[heuristic], options, Player::Blue);
+                assert!((red + blue).abs() < 1e-9, "{:?}: Red {} vs Blue {}", heuristic, red, blue);
+            }
+        }
+    }
+
+    #[test]
+    fn moves_land_in_the_expected_buckets() {
+        // Red's primed edge cell sits between Blue's two primed corners: firing it first takes both,
+        // anything else lets Blue fire into it. Next to the Blue chain Red loses everything.
+        let mut standoff = Board::from_ascii(&["0 0 1B", "0 0 2R", "0 0 1B"]).unwrap();
+        standoff.total_moves = 3;
+        let classification = classify_moves(&standoff);
+        assert_eq!(classification.winning, [(1, 2)]);
+        assert_eq!(classification.risky, [(0, 0), (1, 0), (2, 0)]);
+        assert_eq!(classification.losing, [(0, 1), (1, 1), (2, 1)]);
+        assert!(classification.safe.is_empty());
+
+        // Only the cells next to Blue's primed corner can be taken back.
+        let mut apart = Board::from_ascii(&["1B 0 0", "0 0 0", "0 0 1R"]).unwrap();
+        apart.total_moves = 3;
+        let classification = classify_moves(&apart);
+        assert_eq!(classification.risky, [(0, 1), (1, 0)]);
+        assert_eq!(classification.safe, [(0, 2), (1, 1), (1, 2), (2, 0), (2, 1), (2, 2)]);
+        assert!(classification.winning.is_empty() && classification.losing.is_empty());
+    }
+
+    #[test]
+    fn post_move_evaluation_matches_evaluating_the_played_board() {
+        let board = midgame_board(0xE7A1_0001, 14);
+        let stats = get_ai_move(&board, AIStrategy::AlphaBeta, &TEST_HEURISTICS, EvalOptions::default(), 2, 60_000).unwrap();
+        let mut played = board.clone();
+        played.make_move_for_simulation(stats.chosen.0, stats.chosen.1, None).unwrap();
+
+        for pov in [Player::Red, Player::Blue] {
+            assert_eq!(evaluate_after_move(&board, stats.chosen, &TEST_HEURISTICS, EvalOptions::default(), pov),
+                Ok(evaluate_board(&played, &TEST_HEURISTICS, EvalOptions::default(), pov)));
+        }
+    }
+
+    #[test]
+    fn the_capture_that_punishes_a_blunder_is_the_turning_point() {
+        let start = empty_board(3, 3);
+        let moves = [
+            (2, 0), (2, 2), (2, 0), (2, 0),
+            // Red ignores Blue's primed corner, which blows up on move 6 and takes both Red cells beside it.
+            (1, 1), (2, 0),
+            (2, 0), (2, 1),
+        ];
+        let mut finished = start.clone();
+        finished.apply_moves(&moves).unwrap();
+        assert_eq!(finished.winner(), Some(Player::Blue));
+
+        assert_eq!(find_turning_point(&start, &moves, Player::Blue), Ok(Some(6)));
+        assert!(find_turning_point(&start, &[(2, 0), (2, 0)], Player::Blue).is_err());
+    }
+
+    #[test]
+    fn the_book_opens_with_legal_corner_moves() {
+        for (width, height) in [(6, 9), (5, 5), (10, 10)] {
+            let mut board = empty_board(width, height);
+            for _ in 0..2 {
+                let book_move = opening_book_move(&board).unwrap();
+                assert!(board.get_all_valid_moves().contains(&book_move));
+                let stats = get_ai_move(&board, AIStrategy::AlphaBeta, &TEST_HEURISTICS, EvalOptions::default(), 4, 1_000).unwrap();
+                assert_eq!((stats.chosen, stats.depth_reached), (book_move, 0));
+                board.make_move_for_simulation(book_move.0, book_move.1, None).unwrap();
+            }
+            assert_eq!(opening_book_move(&board), None);
+        }
+        // Sizes the book doesn't know are left to the search.
+        assert_eq!(opening_book_move(&empty_board(4, 7)), None);
+    }
+
+    #[test]
+    fn mobility_favours_the_side_with_more_moves() {
+        // Either side can play any of the six empty cells, but Red can also add to two cells of its own
+        // to Blue's one.
+        let mut board = Board::from_ascii(&["1R 0 0", "0 0 1R", "0 1B 0"]).unwrap();
+        board.total_moves = 3;
+        assert!(heuristic_value(&board, Heuristic::Mobility, Player::Red) > 0.0);
+        assert_eq!(heuristic_value(&board, Heuristic::Mobility, Player::Red), -heuristic_value(&board, Heuristic::Mobility, Player::Blue));
+    }
+
+    #[test]
+    fn pressing_an_opponent_towards_elimination_scores_higher() {
+        // Blue spread over four cells, then down to its last one.
+        let mut balanced = Board::from_ascii(&["1R 1R 0 0", "1R 1R 0 0", "1B 1B 1B 1B"]).unwrap();
+        balanced.total_moves = 8;
+        let mut nearly_won = Board::from_ascii(&["1R 1R 0 0", "1R 1R 0 0", "0 0 0 1B"]).unwrap();
+        nearly_won.total_moves = 8;
+        assert_eq!(near_win_term(&balanced, Player::Red), 0.0);
+        assert_eq!(near_win_term(&nearly_won, Player::Red), NEAR_WIN_CELLS as f64);
+
+        // With no heuristics the bonus is the whole evaluation, large but short of a win.
+        let options = EvalOptions::default();
+        let nearly_won_score = evaluate_board(&nearly_won, &[], options, Player::Red);
+        assert!(nearly_won_score > evaluate_board(&balanced, &[], options, Player::Red));
+        assert!(nearly_won_score.is_finite());
+        assert_eq!(evaluate_board(&nearly_won, &[], options, Player::Blue), -nearly_won_score);
+    }
+
+    #[test]
+    fn influence_credits_the_side_nearer_each_empty_cell() {
+        // Red's centre is one step from four cells and Blue's corner is only that close to two of
+        // them, so Red is nearer three cells and the rest are tied.
+        let board = Board::from_ascii(&["0 0 0", "0 1R 0", "0 0 1B"]).unwrap();
+        assert_eq!(heuristic_value(&board, Heuristic::Influence, Player::Red), 3.0);
+        assert_eq!(heuristic_value(&board, Heuristic::Influence, Player::Blue), -3.0);
+
+        // With no opponent orbs on the board every empty cell is Red's.
+        let board = Board::from_ascii(&["1R 0 0", "0 0 0", "0 0 0"]).unwrap();
+        assert_eq!(heuristic_value(&board, Heuristic::Influence, Player::Red), 8.0);
+    }
+
+    #[test]
+    fn a_primed_stack_next_to_a_primed_enemy_is_vulnerable() {
+        // Red's edge stack and Blue's corner are each one orb from exploding into the other.
+        let exposed = Board::from_ascii(&["0 2R 1B", "0 0 0", "0 0 0"]).unwrap();
+        assert_eq!(heuristic_value(&exposed, Heuristic::Vulnerability, Player::Red), -2.0);
+        assert_eq!(heuristic_value(&exposed, Heuristic::Vulnerability, Player::Blue), -1.0);
+
+        // Blue's centre cell beside the stack needs three more orbs to explode, so it is safe for now.
+        let safe = Board::from_ascii(&["0 2R 0", "0 1B 0", "0 0 0"]).unwrap();
+        assert_eq!(heuristic_value(&safe, Heuristic::Vulnerability, Player::Red), 0.0);
+    }
+
+    #[test]
+    fn a_crowded_board_is_searched_deeper_than_an_empty_one() {
+        // Blue holds all but three cells, leaving Red three moves against an empty board's 100.
+        let mut rows = vec!["1R 0 0 1B 1B 1B"];
+        rows.extend(["1B 1B 1B 1B 1B 1B"; 8]);
+        let mut crowded = Board::from_ascii(&rows).unwrap();
+        crowded.total_moves = 40;
+        assert_eq!(crowded.get_all_valid_moves().len(), 3);
+
+        let empty = empty_board(10, 10);
+        assert!(recommended_depth(&crowded, 3) > recommended_depth(&empty, 3));
+        assert_eq!((recommended_depth(&empty, 3), recommended_depth(&crowded, 3)), (2, 4));
+        // Even the widest board gets a ply.
+        assert_eq!(recommended_depth(&empty, 1), 1);
+    }
+
+    #[test]
+    fn center_control_prefers_the_middle_to_the_corners() {
+        let center = Board::from_ascii(&["0 0 0 0 0", "0 0 1R 0 0", "0 1R 0 1R 0", "0 0 1R 0 0", "0 0 0 0 0"]).unwrap();
+        let corners = Board::from_ascii(&["1R 0 0 0 1R", "0 0 0 0 0", "0 0 0 0 0", "0 0 0 0 0", "1R 0 0 0 1R"]).unwrap();
+        let score = |board: &Board, heuristic| heuristic_value(board, heuristic, Player::Red);
+        assert!(score(&center, Heuristic::CenterControl) > score(&corners, Heuristic::CenterControl));
+        // PeripheralControl leans the other way.
+        assert!(score(&corners, Heuristic::PeripheralControl) > score(&center, Heuristic::PeripheralControl));
+    }
+
+    // The search as it was before negamax: separate maximizing and minimizing branches, every score from
+    // the root player's point of view. Leaves are scored as negamax scores them, quiescence included.
+    fn two_branch_alphabeta(board: &Board, depth: u32, mut alpha: f64, mut beta: f64, maximizing: bool, context: &mut SearchContext) -> f64 {
+        let sign = if maximizing { 1.0 } else { -1.0 };
+        let possible_moves = board.get_all_valid_moves();
+        if board.is_over() || possible_moves.is_empty() {
+            return sign * context.evaluate(board);
+        }
+        if depth == 0 {
+            return sign * quiesce(board, f64::NEG_INFINITY, f64::INFINITY, QUIESCENCE_MAX_DEPTH, context).unwrap();
+        }
+
+        let mut best = if maximizing { f64::NEG_INFINITY } else { f64::INFINITY };
+        for (row, col) in possible_moves {
+            let mut child = board.clone();
+            child.make_move_for_simulation(row, col, None).unwrap();
+            let eval = two_branch_alphabeta(&child, depth - 1, alpha, beta, !maximizing, context);
+            if maximizing {
+                best = best.max(eval);
+                alpha = alpha.max(eval);
+            } else {
+                best = best.min(eval);
+                beta = beta.min(eval);
+            }
+            if beta <= alpha {
+                break;
+            }
+        }
+        best
+    }
+
+    #[test]
+    fn negamax_agrees_with_the_two_branch_search() {
+        let mut compared = 0;
+        for seed in [0x0E6A_0001, 0x0E6A_0002, 0x0E6A_0003, 0x0E6A_0004] {
+            let mut board = empty_board(4, 4);
+            let mut rng = StdRng::seed_from_u64(seed);
+            for _ in 0..8 {
+                let (row, col) = random_move(&board, &mut rng).unwrap();
+                board.make_move_for_simulation(row, col, None).unwrap();
+            }
+            if board.is_over() { continue; }
+            let depth = 3;
+
+            let mut reference_table = TranspositionTable::default();
+            let mut reference = SearchContext::new(&mut reference_table, &TEST_HEURISTICS, EvalOptions::default(), Instant::now() + Duration::from_secs(600), false);
+            let root_scores: Vec<((usize, usize), f64)> = board.get_all_valid_moves().into_iter().map(|(row, col)| {
+                let mut child = board.clone();
+                child.make_move_for_simulation(row, col, None).unwrap();
+                ((row, col), two_branch_alphabeta(&child, depth - 1, f64::NEG_INFINITY, f64::INFINITY, false, &mut reference))
+            }).collect();
+            let best_score = root_scores.iter().map(|&(_, score)| score).fold(f64::NEG_INFINITY, f64::max);
+
+            for pruning in [false, true] {
+                let mut table = TranspositionTable::default();
+                let mut context = SearchContext::new(&mut table, &TEST_HEURISTICS, EvalOptions::default(), Instant::now() + Duration::from_secs(600), pruning);
+                let (chosen, score) = find_best_move_at_depth(&board, depth, None, FULL_WINDOW, &mut context).unwrap();
+                assert_eq!(score, best_score, "seed {:#x}, pruning {}", seed, pruning);
+                // Equally scored moves may be searched in another order, so either search may pick any of them.
+                let chosen_score = root_scores.iter().find(|&&(a_move, _)| a_move == chosen).unwrap().1;
+                assert_eq!(chosen_score, best_score, "seed {:#x}: {:?} isn't one of the best moves", seed, chosen);
+            }
+            compared += 1;
+        }
+        assert!(compared >= 3, "only {} positions were still ongoing", compared);
+    }
+
+    #[test]
+    fn a_cut_off_cascade_can_still_be_evaluated() {
+        // Blue has yet to place an orb, so sweeping up its corner doesn't end the game and the cascade
+        // goes round until the cap stops it, leaving the corner at (2, 2) over critical mass.
+        let mut board = Board::from_ascii(&["1R 2R 1R", "2R 3R 2R", "1R 2R 1B"]).unwrap();
+        board.make_move_with_outcome(0, 0).unwrap();
+        assert!(!board.is_over());
+        assert_eq!(board.cells[2][2].state, CellState::Occupied { player: Player::Red, orbs: 3 });
+        assert_eq!(board.cells[2][2].critical_mass, 2);
+
+        for player in [Player::Red, Player::Blue] {
+            for heuristic in Heuristic::ALL {
+                assert!(heuristic_value(&board, heuristic, player).is_finite(), "{:?}", heuristic);
+            }
+            assert!(evaluate_board(&board, &Heuristic::ALL, EvalOptions::default(), player).is_finite());
+        }
+    }
+}
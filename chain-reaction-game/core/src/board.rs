@@ -0,0 +1,1664 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt;
+use std::sync::Arc;
+use serde::{Deserialize, Serialize};
+use web_time::Instant;
+
+// DTOs are no longer needed here as this module is now pure game logic.
+use crate::game::{Player, Cell, GameResult, GameState, CellState, CriticalMassRule, ForcedCapture, NoMovesRule, OpeningRule, Topology};
+
+// Why a move was rejected or abandoned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MoveError {
+    GameOver,
+    OutOfBounds { row: usize, col: usize },
+    OpponentCell,
+    // A first orb placed where the board's opening rule forbids it.
+    OpeningRule(OpeningRule),
+    // A move that captures nothing while `forced_capture` is on and a capturing move exists.
+    MustCapture,
+    // Only simulated moves have a deadline.
+    SimulationTimeout,
+}
+
+impl fmt::Display for MoveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MoveError::GameOver => write!(f, "The game has already been won."),
+            MoveError::OutOfBounds { row, col } => write!(f, "Move ({}, {}) is out of bounds.", row, col),
+            MoveError::OpponentCell => write!(f, "Cannot place orb in a cell occupied by the opponent."),
+            MoveError::OpeningRule(OpeningRule::NotAdjacentToOpponent) => write!(f, "Your first orb cannot go on or next to an opponent's cell."),
+            MoveError::OpeningRule(OpeningRule::OwnHalf) => write!(f, "Your first orb must go in your own half of the board."),
+            MoveError::OpeningRule(OpeningRule::Anywhere) => write!(f, "Your first orb cannot go there."),
+            MoveError::MustCapture => write!(f, "A capturing move is available, so you must play one."),
+            MoveError::SimulationTimeout => write!(f, "Chain reaction timed out during simulation."),
+        }
+    }
+}
+
+impl std::error::Error for MoveError {}
+
+// Explosions allowed per (cell x critical mass) before a cascade is treated as non-terminating.
+const CHAIN_REACTION_CAP_FACTOR: usize = 4;
+
+// A position reached this many times (same player to move) is a draw.
+const REPETITION_LIMIT: u32 = 3;
+
+// The smallest and largest width or height the app accepts.
+pub const MIN_BOARD_DIM: u32 = 1;
+pub const MAX_BOARD_DIM: u32 = 30;
+
+// --- Zobrist hashing ---
+// A pseudo-random key per (row, col, player, orb count) plus one per side to move. Keys are derived
+// from those values by a fixed mixing function rather than looked up in a table, so every cell of a
+// board of any size and every orb count gets its own key, and hashes are stable across runs.
+const ZOBRIST_SEED: u64 = 0x5EED_C0DE;
+// Keeps the side-to-move keys apart from the cell keys.
+const ZOBRIST_TURN_TAG: u64 = u64::MAX;
+
+// SplitMix64's finaliser: a cheap bijection whose output bits all depend on every input bit.
+fn zobrist_mix(mut x: u64) -> u64 {
+    x = x.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    x = (x ^ (x >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    x ^ (x >> 31)
+}
+
+fn zobrist_cell_key(row: usize, col: usize, player: Player, orbs: u32) -> u64 {
+    [row as u64, col as u64, player.index() as u64, orbs as u64]
+        .into_iter()
+        .fold(ZOBRIST_SEED, |key, value| zobrist_mix(key ^ value))
+}
+
+fn zobrist_turn_key(player: Player) -> u64 {
+    zobrist_mix(zobrist_mix(ZOBRIST_SEED ^ ZOBRIST_TURN_TAG) ^ player.index() as u64)
+}
+
+// The reflections and rotations of a board. The last four only map a board onto itself when it is square.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Symmetry {
+    Identity,
+    MirrorRows,
+    MirrorCols,
+    Rotate180,
+    Transpose,
+    AntiTranspose,
+    Rotate90,
+    Rotate270,
+}
+
+impl Symmetry {
+    // Where the cell at (row, col) lands after applying this symmetry to a width x height board.
+    pub fn apply(self, row: usize, col: usize, width: u32, height: u32) -> (usize, usize) {
+        let last_row = height as usize - 1;
+        let last_col = width as usize - 1;
+        match self {
+            Symmetry::Identity => (row, col),
+            Symmetry::MirrorRows => (last_row - row, col),
+            Symmetry::MirrorCols => (row, last_col - col),
+            Symmetry::Rotate180 => (last_row - row, last_col - col),
+            Symmetry::Transpose => (col, row),
+            Symmetry::AntiTranspose => (last_col - col, last_row - row),
+            Symmetry::Rotate90 => (col, last_row - row),
+            Symmetry::Rotate270 => (last_col - col, row),
+        }
+    }
+}
+
+struct PositionLink {
+    hash: u64,
+    previous: Option<Arc<PositionLink>>,
+}
+
+// The side to move's legal moves, tagged with the position they were listed for.
+struct ValidMoves {
+    // `zobrist_hash()` at the time, which covers the cells and the side to move.
+    hash: u64,
+    // Whether the opening rule still applied.
+    in_opening: bool,
+    // The rules in force, which are public fields and can change without a move being played.
+    opening_rule: OpeningRule,
+    forced_capture: ForcedCapture,
+    moves: Vec<(usize, usize)>,
+}
+
+// One animation frame of a real move: the board right after the cells in `exploded` went off. The
+// settled position at the end has nothing in `exploded`.
+#[derive(Clone)]
+pub struct Frame {
+    pub board: Board,
+    pub exploded: Vec<(usize, usize)>,
+}
+
+// A real move's animation frames plus summary stats, e.g. for an analytics overlay.
+pub struct MoveOutcome {
+    pub history: Vec<Frame>,
+    // Cells that exploded, counting a cell again each time it re-explodes.
+    pub explosion_steps: usize,
+    // Change in each player's orb total; the placed orb counts towards the mover.
+    pub red_delta: i32,
+    pub blue_delta: i32,
+    pub green_delta: i32,
+    pub yellow_delta: i32,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Board {
+    pub width: u32,
+    pub height: u32,
+    pub cells: Vec<Vec<Cell>>,
+    pub orb_counts: HashMap<Player, u32>,
+    pub current_turn: Player,
+    pub game_state: GameState,
+    pub total_moves: u32,
+    pub no_moves_rule: NoMovesRule,
+    #[serde(default)]
+    pub opening_rule: OpeningRule,
+    // The game uses the first `player_count` seats of `Player::ALL`, taking turns in that order
+    // starting from `first_player`. Both default to a Red vs Blue game for older saves.
+    #[serde(default = "default_player_count")]
+    pub player_count: usize,
+    #[serde(default = "default_first_player")]
+    pub first_player: Player,
+    #[serde(default)]
+    pub topology: Topology,
+    #[serde(default)]
+    pub forced_capture: ForcedCapture,
+    // Zobrist hash of the cell contents, kept up to date as cells change. The side to move is
+    // mixed in by zobrist_hash() so code that flips current_turn directly can't desync it.
+    #[serde(skip)]
+    hash: u64,
+    // The position before the last real move, for undo. Each snapshot links to the one before it, and
+    // sharing through Arc keeps the many clones the AI makes of a board cheap.
+    #[serde(skip)]
+    previous: Option<Arc<Board>>,
+    // Zobrist hashes (side to move included) of every position after a move, newest first, for draw
+    // by repetition. A shared list rather than a map so the AI's board clones don't copy it.
+    #[serde(skip)]
+    positions: Option<Arc<PositionLink>>,
+    // Refreshed after every move so the search doesn't rescan the grid at each node. Code that edits
+    // cells or flips `current_turn` directly leaves it stale, and then the moves are listed afresh.
+    #[serde(skip)]
+    valid_moves: Option<Arc<ValidMoves>>,
+    // When the board was created and when the game ended, for `game_result`. A loaded board has no
+    // start time.
+    #[serde(skip)]
+    started_at: Option<Instant>,
+    #[serde(skip)]
+    finished_at: Option<Instant>,
+    // Told about every real move. Saves don't carry it, so a loaded board logs nothing until given one.
+    #[serde(skip, default = "no_logger")]
+    logger: Arc<dyn MoveLogger>,
+}
+
+// Where a board reports the real moves played on it, so the core never touches the filesystem
+// itself. Simulated moves are never reported.
+pub trait MoveLogger: Send + Sync {
+    // Called just before a real move is applied.
+    fn log_move(&self, player: Player, row: usize, col: usize);
+    // Called with the settled position after each real move; `Board::to_log_text` renders it.
+    fn save_position(&self, board: &Board);
+    // Called when a real move's cascade hits the explosion cap and is cut off before it settles.
+    fn chain_reaction_cut_off(&self, _explosions: usize) {}
+}
+
+// The default logger: discards everything.
+pub struct NoopLogger;
+
+impl MoveLogger for NoopLogger {
+    fn log_move(&self, _player: Player, _row: usize, _col: usize) {}
+    fn save_position(&self, _board: &Board) {}
+}
+
+fn no_logger() -> Arc<dyn MoveLogger> {
+    Arc::new(NoopLogger)
+}
+
+fn default_player_count() -> usize {
+    2
+}
+
+fn default_first_player() -> Player {
+    Player::Red
+}
+
+impl Board {
+    // This helper is now in lib.rs, where it belongs.
+    
+    // `player_count` (2 to 4) takes the first seats of `Player::ALL`, and `first_turn` must be one of them.
+    // Turns then go round the seats in that order.
+    pub fn new(width: u32, height: u32, first_turn: Player, player_count: usize, critical_mass_rule: CriticalMassRule, topology: Topology) -> Self {
+        let mut cells = Vec::with_capacity(height as usize);
+        for r in 0..height {
+            let mut row = Vec::with_capacity(width as usize);
+            for c in 0..width {
+                let neighbours = neighbour_cells(r as usize, c as usize, width, height, topology).len() as u32;
+                row.push(Cell::new(critical_mass_rule.critical_mass(r as usize, c as usize, neighbours)));
+            }
+            cells.push(row);
+        }
+        let player_count = player_count.clamp(2, Player::ALL.len());
+        debug_assert!(first_turn.index() < player_count, "first_turn must be one of the active players");
+        let orb_counts = Player::ALL[..player_count].iter().map(|&p| (p, 0)).collect();
+
+        Board { 
+            width, height, cells, orb_counts, 
+            current_turn: first_turn, 
+            game_state: GameState::Ongoing, 
+            total_moves: 0, 
+            no_moves_rule: NoMovesRule::Loss,
+            opening_rule: OpeningRule::Anywhere,
+            player_count,
+            first_player: first_turn,
+            topology,
+            forced_capture: ForcedCapture::default(),
+            hash: 0,
+            previous: None,
+            positions: None,
+            valid_moves: None,
+            started_at: Some(Instant::now()),
+            finished_at: None,
+            logger: no_logger(),
+        }
+    }
+
+    // Builder-style, e.g. `Board::new(...).with_logger(Arc::new(file_logger))`.
+    pub fn with_logger(mut self, logger: Arc<dyn MoveLogger>) -> Self {
+        self.logger = logger;
+        self
+    }
+
+    pub fn set_logger(&mut self, logger: Arc<dyn MoveLogger>) {
+        self.logger = logger;
+    }
+    
+    // Rejects a width or height outside MIN_BOARD_DIM..=MAX_BOARD_DIM: an empty board has nothing to
+    // play on, and a huge one makes every search hang. Single-row and single-column boards are allowed.
+    pub fn validate_dimensions(width: u32, height: u32) -> Result<(), String> {
+        for (name, value) in [("width", width), ("height", height)] {
+            if !(MIN_BOARD_DIM..=MAX_BOARD_DIM).contains(&value) {
+                return Err(format!("Board {} must be between {} and {}, got {}", name, MIN_BOARD_DIM, MAX_BOARD_DIM, value));
+            }
+        }
+        Ok(())
+    }
+
+    // This now returns the Vec of board states for the controller to handle.
+    pub fn make_move_and_get_history(&mut self, row: usize, col: usize) -> Result<Vec<Board>, MoveError> {
+        self.make_move_with_outcome(row, col).map(|outcome| outcome.history.into_iter().map(|frame| frame.board).collect())
+    }
+
+    // Like `make_move_and_get_history`, plus how dramatic the move was.
+    pub fn make_move_with_outcome(&mut self, row: usize, col: usize) -> Result<MoveOutcome, MoveError> {
+        self.logger.log_move(self.current_turn, row, col);
+
+        let orbs_before = self.orb_counts.clone();
+        let result = self.make_move_internal(row, col, true, None).map(|(history, explosions)| {
+            let delta = |player: Player| self.orb_counts.get(&player).copied().unwrap_or(0) as i32 - orbs_before.get(&player).copied().unwrap_or(0) as i32;
+            MoveOutcome {
+                explosion_steps: explosions,
+                red_delta: delta(Player::Red),
+                blue_delta: delta(Player::Blue),
+                green_delta: delta(Player::Green),
+                yellow_delta: delta(Player::Yellow),
+                history,
+            }
+        });
+        // Only resolved positions are saved, so a crash can never leave a half-finished cascade on disk.
+        if self.is_resolved() {
+            self.logger.save_position(self);
+        }
+        result
+    }
+
+    // Restores the position from before the last real move (simulated moves can't be undone).
+    pub fn undo_move(&mut self) -> Result<(), &'static str> {
+        let previous = self.previous.take().ok_or("Nothing to undo.")?;
+        *self = Arc::unwrap_or_clone(previous);
+        Ok(())
+    }
+
+    // True when no cell is at or over critical mass, or the game is over (a winning cascade stops early).
+    pub fn is_resolved(&self) -> bool {
+        self.game_state != GameState::Ongoing
+            || self.cells.iter().flatten().all(|cell| cell.get_explosion_data().is_none())
+    }
+
+    // The player who has won, or None while the game is ongoing or after a draw.
+    pub fn winner(&self) -> Option<Player> {
+        match self.game_state {
+            GameState::Won { winner } => Some(winner),
+            GameState::Ongoing | GameState::Draw => None,
+        }
+    }
+
+    // True once the game has been won or drawn.
+    pub fn is_over(&self) -> bool {
+        self.game_state != GameState::Ongoing
+    }
+
+    // The side to move concedes and the other player wins. Only two-player games can be conceded.
+    pub fn resign(&mut self) -> Result<(), &'static str> {
+        if self.is_over() { return Err("The game is already over."); }
+        if self.player_count != 2 { return Err("Only two-player games can be resigned."); }
+        let winner = self.players().find(|&p| p != self.current_turn).ok_or("No opponent to resign to.")?;
+        self.game_state = GameState::Won { winner };
+        self.note_if_finished();
+        Ok(())
+    }
+
+    // Finishes any cascade left incomplete in a loaded position so the board is stable again.
+    pub fn resolve_pending(&mut self) {
+        self.rehash();
+        self.recalculate_orb_counts();
+        for r in 0..self.height as usize {
+            for c in 0..self.width as usize {
+                if self.game_state != GameState::Ongoing { return; }
+                if self.cells[r][c].get_explosion_data().is_some() {
+                    // Without a deadline the chain reaction cannot fail.
+                    let _ = self.handle_chain_reaction(r, c, false, None, &mut Vec::new());
+                }
+            }
+        }
+        self.recalculate_orb_counts();
+        self.update_game_state();
+        self.refresh_valid_moves();
+        self.note_if_finished();
+    }
+
+    fn note_if_finished(&mut self) {
+        if self.game_state != GameState::Ongoing && self.finished_at.is_none() {
+            self.finished_at = Some(Instant::now());
+        }
+    }
+
+    // The summary of a finished game, or None while it is still being played.
+    pub fn game_result(&self) -> Option<GameResult> {
+        if !self.is_over() { return None; }
+        let winner = self.winner();
+        let duration_ms = match (self.started_at, self.finished_at) {
+            (Some(start), Some(end)) => end.duration_since(start).as_millis() as u64,
+            _ => 0,
+        };
+        Some(GameResult { winner, total_moves: self.total_moves, final_orbs: self.orb_counts.clone(), duration_ms })
+    }
+
+    // The simulation function remains largely the same. Returns the game state the move left behind.
+    pub fn make_move_for_simulation(&mut self, row: usize, col: usize, deadline: Option<&Instant>) -> Result<GameState, MoveError> {
+        self.make_move_internal(row, col, false, deadline).map(|_| self.game_state)
+    }
+
+    // Simulates a move like `make_move_for_simulation` and reports how many explosions it set off.
+    pub fn make_move_counting_explosions(&mut self, row: usize, col: usize) -> Result<usize, MoveError> {
+        self.make_move_internal(row, col, false, None).map(|(_, explosions)| explosions)
+    }
+
+    // The number of explosions playing (row, col) would trigger, without changing this board.
+    pub fn cascade_length(&self, row: usize, col: usize) -> Result<usize, MoveError> {
+        self.clone().make_move_counting_explosions(row, col)
+    }
+
+    /// Plays `moves` in order as simulated moves (nothing is logged and they can't be undone), e.g. to
+    /// set up a position. Stops at the first illegal move and returns its index with the error; the
+    /// moves before it stay applied.
+    ///
+    /// ```
+    /// use chain_reaction_core::board::Board;
+    /// use chain_reaction_core::game::{CellState, CriticalMassRule, Player, Topology};
+    ///
+    /// let mut board = Board::new(6, 9, Player::Red, 2, CriticalMassRule::NeighbourCount, Topology::Grid);
+    /// board.apply_moves(&[(0, 0), (8, 5), (4, 2)]).unwrap();
+    /// assert_eq!(board.current_turn, Player::Blue);
+    /// assert_eq!(board.cells[4][2].state, CellState::Occupied { player: Player::Red, orbs: 1 });
+    ///
+    /// // Blue plays (1, 1), then Red can't take Blue's cell at (8, 5).
+    /// assert!(board.apply_moves(&[(1, 1), (8, 5)]).is_err_and(|(index, _)| index == 1));
+    /// ```
+    pub fn apply_moves(&mut self, moves: &[(usize, usize)]) -> Result<(), (usize, MoveError)> {
+        for (index, &(row, col)) in moves.iter().enumerate() {
+            self.make_move_for_simulation(row, col, None).map_err(|e| (index, e))?;
+        }
+        Ok(())
+    }
+
+    // Returns a history Vec for real moves (empty for simulations) and the number of explosions.
+    // Whether the side to move may play (row, col) now, without playing it.
+    pub fn check_move(&self, row: usize, col: usize) -> Result<(), MoveError> {
+        if self.game_state != GameState::Ongoing { return Err(MoveError::GameOver); }
+        if row >= self.height as usize || col >= self.width as usize { return Err(MoveError::OutOfBounds { row, col }); }
+        if !self.opening_allows(row, col) { return Err(MoveError::OpeningRule(self.opening_rule)); }
+        if let CellState::Occupied { player, .. } = self.cells[row][col].state {
+            if player != self.current_turn { return Err(MoveError::OpponentCell); }
+        }
+        if self.forced_capture.0 && !self.get_all_valid_moves().contains(&(row, col)) { return Err(MoveError::MustCapture); }
+        Ok(())
+    }
+
+    fn make_move_internal(&mut self, row: usize, col: usize, is_real_move: bool, deadline: Option<&Instant>) -> Result<(Vec<Frame>, usize), MoveError> {
+        self.check_move(row, col)?;
+        // A search already past its deadline gets nothing from starting another move.
+        if deadline.is_some_and(|d| Instant::now() >= *d) { return Err(MoveError::SimulationTimeout); }
+        
+        if is_real_move {
+            self.previous = Some(Arc::new(self.clone()));
+        }
+
+        let mut history = Vec::new();
+        self.hash ^= self.cell_key(row, col);
+        self.cells[row][col].add_orb(self.current_turn);
+        self.hash ^= self.cell_key(row, col);
+        *self.orb_count_mut(self.current_turn) += 1;
+        
+        let explosions = self.handle_chain_reaction(row, col, is_real_move, deadline, &mut history)?;
+        
+        self.update_game_state();
+
+        if self.game_state == GameState::Ongoing {
+            self.current_turn = self.next_player();
+            self.refresh_valid_moves();
+            self.detect_no_legal_moves();
+            self.update_repetition_state();
+        }
+        
+        self.total_moves += 1;
+        self.note_if_finished();
+        debug_assert_eq!(self.hash, self.cell_hash_under(Symmetry::Identity), "incremental Zobrist hash out of sync");
+        debug_assert!(self.orb_counts_match_cells(), "incremental orb counts out of sync");
+        
+        if is_real_move {
+            // Add the final state to the history.
+            history.push(Frame { board: self.clone(), exploded: Vec::new() });
+        }
+
+        Ok((history, explosions))
+    }
+    
+    // Full recount, for positions built or loaded cell by cell. Moves keep `orb_counts` up to date
+    // as they go.
+    fn recalculate_orb_counts(&mut self) {
+        let counts = self.count_orbs();
+        for player in self.players() {
+            self.orb_counts.insert(player, counts[player.index()]);
+        }
+    }
+
+    // Orbs on the board per player, indexed by `Player::index`.
+    fn count_orbs(&self) -> [u32; Player::ALL.len()] {
+        let mut counts = [0; Player::ALL.len()];
+        for cell in self.cells.iter().flatten() {
+            if let CellState::Occupied { player, orbs } = cell.state {
+                counts[player.index()] += orbs;
+            }
+        }
+        counts
+    }
+
+    // Whether the incrementally kept `orb_counts` agree with a fresh recount of the cells.
+    fn orb_counts_match_cells(&self) -> bool {
+        let counts = self.count_orbs();
+        self.players().all(|p| self.orb_counts.get(&p).copied().unwrap_or(0) == counts[p.index()])
+    }
+
+    fn orb_count_mut(&mut self, player: Player) -> &mut u32 {
+        self.orb_counts.entry(player).or_insert(0)
+    }
+
+    // Whoever moves after the current player, called before `total_moves` counts the move just made.
+    // Players who have had their first move and since lost every orb are out and get skipped.
+    fn next_player(&self) -> Player {
+        let seat = self.seat_of(self.current_turn);
+        (1..self.player_count)
+            .map(|offset| (seat + offset) % self.player_count)
+            .find(|&next_seat| next_seat as u32 > self.total_moves || self.orb_counts.get(&self.player_at(next_seat)).is_some_and(|&orbs| orbs > 0))
+            .map_or(self.current_turn, |next_seat| self.player_at(next_seat))
+    }
+
+    // The players in this game, in turn order starting with whoever moved first.
+    pub fn players(&self) -> impl Iterator<Item = Player> {
+        let (first, count) = (self.first_player.index(), self.player_count);
+        (0..count).map(move |seat| Player::ALL[(first + seat) % count])
+    }
+
+    // Everyone but `player` who is still in the game, in turn order.
+    pub fn opponents(&self, player: Player) -> impl Iterator<Item = Player> + '_ {
+        self.players().filter(move |&other| other != player && !self.is_eliminated(other))
+    }
+
+    // Whether `player` has had a turn and since lost every orb, which puts them out of the game.
+    pub fn is_eliminated(&self, player: Player) -> bool {
+        (self.seat_of(player) as u32) < self.total_moves && self.orb_counts.get(&player).is_none_or(|&orbs| orbs == 0)
+    }
+
+    // How many turns after the first player `player` moves in each round.
+    fn seat_of(&self, player: Player) -> usize {
+        (player.index() + self.player_count - self.first_player.index()) % self.player_count
+    }
+
+    fn player_at(&self, seat: usize) -> Player {
+        Player::ALL[(self.first_player.index() + seat) % self.player_count]
+    }
+    
+    // Now only populates a history vec instead of emitting events.
+    fn handle_chain_reaction(&mut self, start_row: usize, start_col: usize, is_real_move: bool, deadline: Option<&Instant>, history: &mut Vec<Frame>) -> Result<usize, MoveError> {
+        let mut exploding_cells: VecDeque<(usize, usize)> = VecDeque::new();
+        let mut explosions = 0;
+        // A real game is decided long before this many explosions; hitting it means the cascade
+        // would never settle, so it is cut off instead of hanging the caller.
+        let max_critical_mass = self.cells.iter().flatten().map(|cell| cell.critical_mass).max().unwrap_or(1) as usize;
+        let explosion_cap = self.width as usize * self.height as usize * max_critical_mass * CHAIN_REACTION_CAP_FACTOR;
+        
+        if self.cells[start_row][start_col].get_explosion_data().is_some() {
+            exploding_cells.push_back((start_row, start_col));
+            self.cells[start_row][start_col].is_queued = true;
+        }
+
+        while let Some((r, c)) = exploding_cells.pop_front() {
+            // Checked before every explosion, so even a board-wide cascade stops within one step of
+            // the deadline.
+            if deadline.is_some_and(|d| Instant::now() >= *d) {
+                return Err(MoveError::SimulationTimeout);
+            }
+
+            if explosions >= explosion_cap {
+                if is_real_move {
+                    self.logger.chain_reaction_cut_off(explosions);
+                }
+                self.cells[r][c].is_queued = false;
+                for (qr, qc) in exploding_cells.drain(..) {
+                    self.cells[qr][qc].is_queued = false;
+                }
+                break;
+            }
+
+            if let Some((exploding_player, current_orbs)) = self.cells[r][c].get_explosion_data() {
+                explosions += 1;
+                let crit_mass = self.cells[r][c].critical_mass;
+                let remaining_orbs = current_orbs.saturating_sub(crit_mass);
+                *self.orb_count_mut(exploding_player) -= current_orbs - remaining_orbs;
+                self.hash ^= self.cell_key(r, c);
+                self.cells[r][c].state = if remaining_orbs > 0 { CellState::Occupied { player: exploding_player, orbs: remaining_orbs } } else { CellState::Empty };
+                self.hash ^= self.cell_key(r, c);
+                self.cells[r][c].is_queued = false;
+
+                for (nr, nc) in self.neighbors(r, c) {
+                    // The neighbour's orbs change hands along with the one it receives.
+                    if let CellState::Occupied { player, orbs } = self.cells[nr][nc].state {
+                        if player != exploding_player {
+                            *self.orb_count_mut(player) -= orbs;
+                            *self.orb_count_mut(exploding_player) += orbs;
+                        }
+                    }
+                    *self.orb_count_mut(exploding_player) += 1;
+                    self.hash ^= self.cell_key(nr, nc);
+                    self.cells[nr][nc].take_over(exploding_player);
+                    self.hash ^= self.cell_key(nr, nc);
+                    let neighbor_cell = &mut self.cells[nr][nc];
+                    if neighbor_cell.get_explosion_data().is_some() && !neighbor_cell.is_queued {
+                        exploding_cells.push_back((nr, nc));
+                        neighbor_cell.is_queued = true;
+                    }
+                }
+                
+                let cell_after_explosion = &mut self.cells[r][c];
+                if cell_after_explosion.get_explosion_data().is_some() && !cell_after_explosion.is_queued {
+                    exploding_cells.push_back((r, c));
+                    cell_after_explosion.is_queued = true;
+                }
+                
+                // If it's a real move, save the intermediate state for animation. Its orb totals
+                // are shown as-is, so they must already match the cells mid-cascade.
+                if is_real_move {
+                    history.push(Frame { board: self.clone(), exploded: vec![(r, c)] });
+                }
+                
+                self.update_game_state();
+                if self.game_state != GameState::Ongoing {
+                    break; 
+                }
+            }
+        }
+        Ok(explosions)
+    }
+    
+    // Draws the game once the position after a move has occurred REPETITION_LIMIT times.
+    fn update_repetition_state(&mut self) {
+        if self.game_state != GameState::Ongoing { return; }
+        let hash = self.zobrist_hash();
+        let mut seen = 1;
+        let mut link = self.positions.as_deref();
+        while let Some(position) = link {
+            if position.hash == hash { seen += 1; }
+            link = position.previous.as_deref();
+        }
+        self.positions = Some(Arc::new(PositionLink { hash, previous: self.positions.take() }));
+        if seen >= REPETITION_LIMIT {
+            self.game_state = GameState::Draw;
+        }
+    }
+
+    // Once everyone has moved, the last player with orbs left wins.
+    fn update_game_state(&mut self) {
+        if self.total_moves < self.player_count as u32 { return; }
+
+        let mut remaining = self.players().filter(|p| self.orb_counts.get(p).is_some_and(|&orbs| orbs > 0));
+        if let (Some(winner), None) = (remaining.next(), remaining.next()) {
+            self.game_state = GameState::Won { winner };
+        }
+    }
+
+    // The orthogonal neighbours of a cell that lie on the board. On a torus they wrap around the
+    // edges, so every cell has four (on a 2-wide board both sideways neighbours are the same cell).
+    pub fn neighbors(&self, row: usize, col: usize) -> Vec<(usize, usize)> {
+        neighbour_cells(row, col, self.width, self.height, self.topology)
+    }
+
+    // A cell is contested when it borders at least one cell owned by a different player.
+    pub fn is_contested(&self, row: usize, col: usize) -> bool {
+        let owner = match self.cells[row][col].state {
+            CellState::Occupied { player, .. } => player,
+            CellState::Empty => return false,
+        };
+        self.neighbors(row, col).into_iter().any(|(nr, nc)| {
+            matches!(self.cells[nr][nc].state, CellState::Occupied { player, .. } if player != owner)
+        })
+    }
+
+    // How many cells `player` currently occupies.
+    pub fn cell_count(&self, player: Player) -> usize {
+        self.cells.iter().flatten()
+            .filter(|cell| matches!(cell.state, CellState::Occupied { player: owner, .. } if owner == player))
+            .count()
+    }
+
+    // Whether `player` can see this cell under fog of war: it is theirs or orthogonally next to one of theirs.
+    pub fn is_visible_to(&self, player: Player, row: usize, col: usize) -> bool {
+        let owned = |(r, c): (usize, usize)| matches!(self.cells[r][c].state, CellState::Occupied { player: owner, .. } if owner == player);
+        owned((row, col)) || self.neighbors(row, col).into_iter().any(owned)
+    }
+
+    // A copy of the position without the undo history, so keeping many positions costs one board each.
+    pub fn snapshot(&self) -> Board {
+        let mut snapshot = self.clone();
+        snapshot.previous = None;
+        snapshot
+    }
+
+    // The board as `player` sees it under fog of war: every cell they can't see is shown empty, and the
+    // orb counts only cover what is visible. The undo history is dropped so nothing hidden rides along.
+    pub fn visible_to(&self, player: Player) -> Board {
+        let mut masked = self.clone();
+        for r in 0..self.height as usize {
+            for c in 0..self.width as usize {
+                if !self.is_visible_to(player, r, c) {
+                    masked.cells[r][c].state = CellState::Empty;
+                }
+            }
+        }
+        masked.previous = None;
+        masked.recalculate_orb_counts();
+        masked.rehash();
+        masked
+    }
+
+    // Cells owned by `player` that sit one orb below critical mass.
+    pub fn primed_cells(&self, player: Player) -> Vec<(usize, usize)> {
+        let mut primed = Vec::new();
+        for r in 0..self.height as usize {
+            for c in 0..self.width as usize {
+                let cell = &self.cells[r][c];
+                if let CellState::Occupied { player: owner, orbs } = cell.state {
+                    if owner == player && orbs + 1 == cell.critical_mass {
+                        primed.push((r, c));
+                    }
+                }
+            }
+        }
+        primed
+    }
+
+    // A position is quiet when no primed cell (one orb below critical mass) borders an opponent,
+    // i.e. nobody can capture anything with a single explosion next move.
+    pub fn is_quiet(&self) -> bool {
+        for r in 0..self.height as usize {
+            for c in 0..self.width as usize {
+                let cell = &self.cells[r][c];
+                if let CellState::Occupied { orbs, .. } = cell.state {
+                    if orbs + 1 == cell.critical_mass && self.is_contested(r, c) {
+                        return false;
+                    }
+                }
+            }
+        }
+        true
+    }
+
+    // Share of occupied cells (any player) sitting one orb below critical mass: 0.0 on a calm board,
+    // approaching 1.0 when almost anything will set off a chain reaction.
+    pub fn volatility(&self) -> f64 {
+        let occupied: usize = self.players().map(|p| self.cell_count(p)).sum();
+        if occupied == 0 {
+            return 0.0;
+        }
+        let primed: usize = self.players().map(|p| self.primed_cells(p).len()).sum();
+        primed as f64 / occupied as f64
+    }
+
+    // Cells the opponent can't reach without first breaking through `player`'s cells. Flood fills
+    // from the opponent's cells through empty and opponent cells; anything left over that doesn't
+    // border the flood is walled off by `player` and the board edges. Before the opponent has placed
+    // anything they can drop an orb anywhere, so nothing counts as sealed yet.
+    pub fn sealed_territory(&self, player: Player) -> usize {
+        let mut reached = vec![vec![false; self.width as usize]; self.height as usize];
+        let mut stack = Vec::new();
+        for (r, row) in self.cells.iter().enumerate() {
+            for (c, cell) in row.iter().enumerate() {
+                if matches!(cell.state, CellState::Occupied { player: owner, .. } if owner != player) {
+                    reached[r][c] = true;
+                    stack.push((r, c));
+                }
+            }
+        }
+        if stack.is_empty() {
+            return 0;
+        }
+
+        while let Some((r, c)) = stack.pop() {
+            for (nr, nc) in self.neighbors(r, c) {
+                let walls = matches!(self.cells[nr][nc].state, CellState::Occupied { player: owner, .. } if owner == player);
+                if !reached[nr][nc] && !walls {
+                    reached[nr][nc] = true;
+                    stack.push((nr, nc));
+                }
+            }
+        }
+
+        let mut sealed = 0;
+        for r in 0..self.height as usize {
+            for c in 0..self.width as usize {
+                let on_frontier = self.neighbors(r, c).into_iter().any(|(nr, nc)| reached[nr][nc]);
+                if !reached[r][c] && !on_frontier {
+                    sealed += 1;
+                }
+            }
+        }
+        sealed
+    }
+
+    // For each player, how many of their cells hold 0, 1, 2, ... orbs. All vectors share one length,
+    // long enough for the largest critical mass or stack on the board.
+    pub fn orb_histogram(&self) -> HashMap<Player, Vec<u32>> {
+        let longest = self.cells.iter().flatten().map(|cell| match cell.state {
+            CellState::Occupied { orbs, .. } => orbs.max(cell.critical_mass),
+            CellState::Empty => cell.critical_mass,
+        }).max().unwrap_or(0) as usize;
+
+        let mut histogram: HashMap<Player, Vec<u32>> = self.players().map(|p| (p, vec![0; longest + 1])).collect();
+        for cell in self.cells.iter().flatten() {
+            if let CellState::Occupied { player, orbs } = cell.state {
+                histogram.get_mut(&player).unwrap()[orbs as usize] += 1;
+            }
+        }
+        histogram
+    }
+
+    // The symmetries that map this board's shape onto itself.
+    pub fn symmetries(&self) -> Vec<Symmetry> {
+        let mut symmetries = vec![Symmetry::Identity, Symmetry::MirrorRows, Symmetry::MirrorCols, Symmetry::Rotate180];
+        if self.width == self.height {
+            symmetries.extend([Symmetry::Transpose, Symmetry::AntiTranspose, Symmetry::Rotate90, Symmetry::Rotate270]);
+        }
+        symmetries
+    }
+
+    // The key of the cell's current contents (0 for an empty cell).
+    fn cell_key(&self, row: usize, col: usize) -> u64 {
+        match self.cells[row][col].state {
+            CellState::Occupied { player, orbs } => zobrist_cell_key(row, col, player, orbs),
+            CellState::Empty => 0,
+        }
+    }
+
+    fn turn_key(&self) -> u64 {
+        zobrist_turn_key(self.current_turn)
+    }
+
+    // Full recompute of the cell part of the hash, optionally under a symmetry.
+    fn cell_hash_under(&self, symmetry: Symmetry) -> u64 {
+        let mut hash = 0;
+        for r in 0..self.height as usize {
+            for c in 0..self.width as usize {
+                if let CellState::Occupied { player, orbs } = self.cells[r][c].state {
+                    let (tr, tc) = symmetry.apply(r, c, self.width, self.height);
+                    hash ^= zobrist_cell_key(tr, tc, player, orbs);
+                }
+            }
+        }
+        hash
+    }
+
+    // O(1): the cell hash is maintained incrementally by every move.
+    pub fn zobrist_hash(&self) -> u64 {
+        self.hash ^ self.turn_key()
+    }
+
+    // The full position (cells, orb counts, side to move, game state, move count) as JSON. Undo
+    // history is not included.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("Board always serializes")
+    }
+
+    // Inverse of `to_json`. Rejects a cell grid that doesn't match the stored dimensions.
+    pub fn from_json(s: &str) -> Result<Board, serde_json::Error> {
+        use serde::de::Error;
+
+        let mut board: Board = serde_json::from_str(s)?;
+        if board.cells.len() != board.height as usize || board.cells.iter().any(|row| row.len() != board.width as usize) {
+            return Err(serde_json::Error::custom("cell grid does not match the board's width and height"));
+        }
+        board.rehash();
+        Ok(board)
+    }
+
+    // Builds a position from rows in the format `to_log_text` writes ("2R 0 1B"; G and Y for the
+    // other seats). Critical masses follow the neighbour count on an ordinary grid, the player count
+    // covers every colour that appears (at least two), and Red is to move with the move count at 0.
+    pub fn from_ascii(rows: &[&str]) -> Result<Board, String> {
+        let grid = rows.iter()
+            .map(|line| line.split_whitespace().map(parse_ascii_cell).collect::<Result<Vec<_>, _>>())
+            .collect::<Result<Vec<_>, _>>()?;
+        let width = grid.first().map_or(0, |row| row.len());
+        if width == 0 {
+            return Err("The board needs at least one row and one column.".to_string());
+        }
+        if let Some(row) = grid.iter().position(|row| row.len() != width) {
+            return Err(format!("Row {} has {} cells, expected {}.", row, grid[row].len(), width));
+        }
+
+        let player_count = grid.iter().flatten()
+            .filter_map(|state| match state {
+                CellState::Occupied { player, .. } => Some(player.index() + 1),
+                CellState::Empty => None,
+            })
+            .max().unwrap_or(0).max(2);
+        let mut board = Board::new(width as u32, grid.len() as u32, Player::Red, player_count, CriticalMassRule::NeighbourCount, Topology::Grid);
+        for (row, states) in grid.into_iter().enumerate() {
+            for (col, state) in states.into_iter().enumerate() {
+                board.cells[row][col].state = state;
+            }
+        }
+        board.recalculate_orb_counts();
+        board.rehash();
+        Ok(board)
+    }
+
+    // Replays a move log of "Red 3 4" lines (as a `MoveLogger` is handed them) on an empty `width` x
+    // `height` board and returns the position after each move. Whoever moves first starts, the player
+    // count covers every colour in the log, and blank lines are skipped. Errors name the 1-based line.
+    pub fn replay_log(content: &str, width: u32, height: u32) -> Result<Vec<Board>, String> {
+        let mut moves = Vec::new();
+        for (index, line) in content.lines().enumerate() {
+            if line.trim().is_empty() { continue; }
+            let parsed = match line.split_whitespace().collect::<Vec<_>>()[..] {
+                [player, row, col] => parse_player_name(player).zip(row.parse::<usize>().ok()).zip(col.parse::<usize>().ok()),
+                _ => None,
+            };
+            let ((player, row), col) = parsed.ok_or_else(|| format!("Line {}: expected \"<player> <row> <col>\", got \"{}\"", index + 1, line))?;
+            moves.push((index + 1, player, row, col));
+        }
+
+        let Some(&(_, first_player, _, _)) = moves.first() else { return Ok(Vec::new()); };
+        let player_count = moves.iter().map(|&(_, player, _, _)| player.index() + 1).max().unwrap_or(0).max(2);
+        let mut board = Board::new(width, height, first_player, player_count, CriticalMassRule::NeighbourCount, Topology::Grid);
+
+        let mut snapshots = Vec::with_capacity(moves.len());
+        for (line, player, row, col) in moves {
+            if player != board.current_turn {
+                return Err(format!("Line {}: {:?} moved, but it was {:?}'s turn", line, player, board.current_turn));
+            }
+            board.make_move_for_simulation(row, col, None).map_err(|e| format!("Line {}: {}", line, e))?;
+            snapshots.push(board.clone());
+        }
+        Ok(snapshots)
+    }
+
+    // Resynchronises the maintained hash and move list after cells were edited directly (e.g. loading a position).
+    pub fn rehash(&mut self) {
+        self.hash = self.cell_hash_under(Symmetry::Identity);
+        self.refresh_valid_moves();
+    }
+
+    // The smallest Zobrist hash over all of the board's symmetries, so mirrored or rotated
+    // copies of a position share one key (e.g. a single opening book entry).
+    pub fn canonical_hash(&self) -> u64 {
+        self.symmetries().into_iter().map(|s| self.cell_hash_under(s) ^ self.turn_key()).min().unwrap_or(0)
+    }
+
+    // Ends the game when the side to move has nowhere to play, per `no_moves_rule`.
+    fn detect_no_legal_moves(&mut self) {
+        if self.game_state != GameState::Ongoing || !self.get_all_valid_moves().is_empty() { return; }
+
+        self.game_state = match self.no_moves_rule {
+            NoMovesRule::Loss => {
+                let mut others = self.players().filter(|&p| p != self.current_turn && self.orb_counts.get(&p).is_some_and(|&orbs| orbs > 0));
+                let winner = match (others.next(), others.next()) {
+                    (Some(winner), None) => Some(winner),
+                    _ => None,
+                };
+                match winner {
+                    Some(winner) => GameState::Won { winner },
+                    // With more than two players the stuck player sits out and the rest play on.
+                    None => {
+                        self.current_turn = self.next_player();
+                        self.refresh_valid_moves();
+                        return self.detect_no_legal_moves();
+                    }
+                }
+            }
+            NoMovesRule::Draw => GameState::Draw,
+        };
+    }
+
+    // Whether `opening_rule` lets the side to move play (row, col). Only the first round of moves,
+    // when each player places their first orb, is restricted.
+    fn opening_allows(&self, row: usize, col: usize) -> bool {
+        if !self.in_opening() { return true; }
+        match self.opening_rule {
+            OpeningRule::Anywhere => true,
+            OpeningRule::NotAdjacentToOpponent => {
+                let held_by_opponent = |(r, c): (usize, usize)| matches!(self.cells[r][c].state, CellState::Occupied { player, .. } if player != self.current_turn);
+                !held_by_opponent((row, col)) && !self.neighbors(row, col).into_iter().any(held_by_opponent)
+            }
+            OpeningRule::OwnHalf => {
+                let height = self.height as usize;
+                if self.current_turn.index().is_multiple_of(2) { 2 * row < height } else { 2 * row + 1 >= height }
+            }
+        }
+    }
+
+    /// The side to move's legal moves, from the list kept since the last move when it still applies.
+    /// Under `forced_capture` only the capturing moves are listed whenever there is one.
+    ///
+    /// ```
+    /// use chain_reaction_core::board::Board;
+    /// use chain_reaction_core::game::{CriticalMassRule, ForcedCapture, Player, Topology};
+    ///
+    /// let mut board = Board::new(3, 3, Player::Red, 2, CriticalMassRule::NeighbourCount, Topology::Grid);
+    /// board.forced_capture = ForcedCapture(true);
+    /// // Red's corner (0, 0) is one orb from exploding into Blue's cell at (0, 1).
+    /// board.apply_moves(&[(0, 0), (0, 1)]).unwrap();
+    /// assert_eq!(board.get_all_valid_moves(), vec![(0, 0)]);
+    /// assert!(board.check_move(2, 2).is_err());
+    /// ```
+    pub fn get_all_valid_moves(&self) -> Vec<(usize, usize)> {
+        match &self.valid_moves {
+            Some(cached) if cached.hash == self.zobrist_hash() && cached.in_opening == self.in_opening()
+                && cached.opening_rule == self.opening_rule && cached.forced_capture == self.forced_capture => cached.moves.clone(),
+            _ => self.scan_valid_moves(),
+        }
+    }
+
+    // The legal moves with mirror and rotation duplicates dropped: when the position maps onto itself
+    // under a symmetry, a move and its image lead to equivalent games, so only the first of each such
+    // group (in row-major order) is kept. On an asymmetric position this is every legal move.
+    pub fn canonical_moves(&self) -> Vec<(usize, usize)> {
+        let moves = self.get_all_valid_moves();
+        let symmetries: Vec<Symmetry> = self.symmetries().into_iter()
+            .filter(|&s| s != Symmetry::Identity && self.is_symmetric_under(s))
+            .collect();
+        if symmetries.is_empty() { return moves; }
+
+        let legal: HashSet<(usize, usize)> = moves.iter().copied().collect();
+        moves.into_iter()
+            .filter(|&(row, col)| symmetries.iter().all(|s| {
+                let image = s.apply(row, col, self.width, self.height);
+                image >= (row, col) || !legal.contains(&image)
+            }))
+            .collect()
+    }
+
+    // Whether the symmetry maps every cell's contents and critical mass onto an identical cell.
+    fn is_symmetric_under(&self, symmetry: Symmetry) -> bool {
+        (0..self.height as usize).all(|r| (0..self.width as usize).all(|c| {
+            let (tr, tc) = symmetry.apply(r, c, self.width, self.height);
+            let (cell, image) = (&self.cells[r][c], &self.cells[tr][tc]);
+            cell.state == image.state && cell.critical_mass == image.critical_mass
+        }))
+    }
+
+    fn refresh_valid_moves(&mut self) {
+        self.valid_moves = Some(Arc::new(ValidMoves {
+            hash: self.zobrist_hash(),
+            in_opening: self.in_opening(),
+            opening_rule: self.opening_rule,
+            forced_capture: self.forced_capture,
+            moves: self.scan_valid_moves(),
+        }));
+    }
+
+    // Whether players are still placing their first orbs, where `opening_rule` applies.
+    fn in_opening(&self) -> bool {
+        self.total_moves < self.player_count as u32
+    }
+
+    fn scan_valid_moves(&self) -> Vec<(usize, usize)> {
+        let mut valid_moves = Vec::new();
+        for r in 0..self.height as usize {
+            for c in 0..self.width as usize {
+                if !self.opening_allows(r, c) { continue; }
+                match self.cells[r][c].state {
+                    CellState::Empty => {
+                        valid_moves.push((r, c));
+                    }
+                    CellState::Occupied { player, .. } => {
+                        if player == self.current_turn {
+                            valid_moves.push((r, c));
+                        }
+                    }
+                }
+            }
+        }
+        if self.forced_capture.0 {
+            let captures: Vec<(usize, usize)> = valid_moves.iter().copied().filter(|&(r, c)| self.captures(r, c)).collect();
+            if !captures.is_empty() { return captures; }
+        }
+        valid_moves
+    }
+
+    // Whether playing (row, col) leaves some opponent with fewer orbs. Only a cell about to explode
+    // can reach another player's orbs, so the rest are ruled out without simulating.
+    fn captures(&self, row: usize, col: usize) -> bool {
+        if !self.cells[row][col].explodes_on_next_orb() { return false; }
+        let mut after = self.clone();
+        // The simulated move must not ask for the capture list again.
+        after.forced_capture = ForcedCapture(false);
+        if after.make_move_for_simulation(row, col, None).is_err() { return false; }
+        self.players().any(|p| p != self.current_turn && after.orb_counts.get(&p) < self.orb_counts.get(&p))
+    }
+
+    // The position as a log file holds it: a header line, then one row of cells per line.
+    pub fn to_log_text(&self) -> String {
+        // Write header based on current player, with the side to move and move count for recovery
+        let move_type = match self.current_turn {
+            Player::Red => "Human Move",
+            Player::Blue | Player::Green | Player::Yellow => "AI Move",
+        };
+        let mut text = format!("{}: {:?} to move, total moves {}\n", move_type, self.current_turn, self.total_moves);
+        
+        // Write board state
+        for row in &self.cells {
+            let mut row_parts = Vec::new();
+            for cell in row {
+                match cell.state {
+                    CellState::Empty => row_parts.push("0".to_string()),
+                    CellState::Occupied { player, orbs } => {
+                        row_parts.push(format!("{}{}", orbs, player_letter(player)));
+                    }
+                }
+            }
+            text.push_str(&row_parts.join(" "));
+            text.push('\n');
+        }
+        text
+    }
+
+}
+
+// A header with the side to move, game state and orb totals, then the grid as `[2R] [ ]` rows.
+impl fmt::Display for Board {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let orbs: Vec<String> = self.players()
+            .map(|player| format!("{}-{}", player_letter(player), self.orb_counts.get(&player).copied().unwrap_or(0)))
+            .collect();
+        writeln!(f, "--- Turn: {:?} | Game: {:?} | Orbs: {} ---", self.current_turn, self.game_state, orbs.join(" "))?;
+        for row in &self.cells {
+            for cell in row {
+                match cell.state {
+                    CellState::Empty => write!(f, "[ ] ")?,
+                    CellState::Occupied { player, orbs } => write!(f, "[{}{}] ", orbs, player_letter(player))?,
+                }
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+// `Board::neighbors` for a board that may not exist yet. Counting the cells that are actually on the
+// board (rather than subtracting edges from 4) keeps 1-wide and 1-tall boards right.
+fn neighbour_cells(row: usize, col: usize, width: u32, height: u32, topology: Topology) -> Vec<(usize, usize)> {
+    let offsets: [(isize, isize); 4] = [(-1, 0), (1, 0), (0, -1), (0, 1)];
+    if topology == Topology::Torus {
+        let (height, width) = (height as isize, width as isize);
+        return offsets.iter().map(|(dr, dc)| {
+            ((row as isize + dr).rem_euclid(height) as usize, (col as isize + dc).rem_euclid(width) as usize)
+        }).collect();
+    }
+    offsets.iter().filter_map(|(dr, dc)| {
+        let nr = row as isize + dr;
+        let nc = col as isize + dc;
+        if nr >= 0 && nr < height as isize && nc >= 0 && nc < width as isize {
+            Some((nr as usize, nc as usize))
+        } else {
+            None
+        }
+    }).collect()
+}
+
+// The letter a player's orbs are marked with in `to_log_text` and the Display grid.
+fn player_letter(player: Player) -> char {
+    match player {
+        Player::Red => 'R',
+        Player::Blue => 'B',
+        Player::Green => 'G',
+        Player::Yellow => 'Y',
+    }
+}
+
+// One cell of a `to_log_text` row: "0" for empty, otherwise an orb count and a player letter.
+fn parse_ascii_cell(token: &str) -> Result<CellState, String> {
+    if token == "0" {
+        return Ok(CellState::Empty);
+    }
+    let invalid = || format!("Invalid cell: {}", token);
+    let split = token.find(|c: char| !c.is_ascii_digit()).ok_or_else(invalid)?;
+    let orbs = token[..split].parse::<u32>().ok().filter(|&orbs| orbs > 0).ok_or_else(invalid)?;
+    let player = match &token[split..] {
+        "R" => Player::Red,
+        "B" => Player::Blue,
+        "G" => Player::Green,
+        "Y" => Player::Yellow,
+        _ => return Err(invalid()),
+    };
+    Ok(CellState::Occupied { player, orbs })
+}
+
+// A player as `{:?}` prints it ("Red", "Blue", ...).
+pub fn parse_player_name(name: &str) -> Option<Player> {
+    Player::ALL.into_iter().find(|player| format!("{:?}", player) == name)
+}
+
+// Reads the side to move and move count back out of a header written by `to_log_text`
+// ("AI Move: Blue to move, total moves 12"). None for anything else, including older logs whose
+// header was just "AI Move:".
+pub fn parse_log_header(line: &str) -> Option<(Player, u32)> {
+    let (_, details) = line.split_once(':')?;
+    let (turn, moves) = details.split_once(',')?;
+    let current_turn = parse_player_name(turn.trim().strip_suffix(" to move")?)?;
+    let total_moves = moves.trim().strip_prefix("total moves ")?.parse().ok()?;
+    Some((current_turn, total_moves))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    // Remembers every cut-off cascade it is told about.
+    #[derive(Default)]
+    struct CutOffRecorder {
+        cut_offs: Mutex<Vec<usize>>,
+    }
+
+    impl MoveLogger for CutOffRecorder {
+        fn log_move(&self, _player: Player, _row: usize, _col: usize) {}
+        fn save_position(&self, _board: &Board) {}
+        fn chain_reaction_cut_off(&self, explosions: usize) {
+            self.cut_offs.lock().unwrap().push(explosions);
+        }
+    }
+
+    #[test]
+    fn saturated_two_by_two_cascade_is_cut_off() {
+        // Every cell is one orb short of exploding and nobody else has moved, so no one can win and
+        // the cascade would go round the board forever.
+        let recorder = Arc::new(CutOffRecorder::default());
+        let mut board = Board::from_ascii(&["1R 1R", "1R 1R"]).unwrap().with_logger(recorder.clone());
+
+        let outcome = board.make_move_with_outcome(0, 0).unwrap();
+
+        let cap = 2 * 2 * 2 * CHAIN_REACTION_CAP_FACTOR;
+        assert_eq!(outcome.explosion_steps, cap);
+        assert_eq!(*recorder.cut_offs.lock().unwrap(), vec![cap]);
+        assert!(board.cells.iter().flatten().all(|cell| !cell.is_queued));
+    }
+
+    #[test]
+    fn simulated_cascade_is_cut_off_silently() {
+        let recorder = Arc::new(CutOffRecorder::default());
+        let mut board = Board::from_ascii(&["1R 1R", "1R 1R"]).unwrap().with_logger(recorder.clone());
+
+        assert_eq!(board.make_move_counting_explosions(0, 0), Ok(2 * 2 * 2 * CHAIN_REACTION_CAP_FACTOR));
+        assert!(recorder.cut_offs.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn move_order_does_not_change_the_hash() {
+        let new_board = || Board::new(5, 5, Player::Red, 2, CriticalMassRule::NeighbourCount, Topology::Grid);
+        let mut one_way = new_board();
+        one_way.apply_moves(&[(0, 0), (4, 4), (2, 2), (4, 0)]).unwrap();
+        let mut other_way = new_board();
+        other_way.apply_moves(&[(2, 2), (4, 0), (0, 0), (4, 4)]).unwrap();
+
+        assert_eq!(one_way.zobrist_hash(), other_way.zobrist_hash());
+        assert_eq!(one_way.zobrist_hash(), one_way.cell_hash_under(Symmetry::Identity) ^ one_way.turn_key());
+        other_way.current_turn = Player::Blue;
+        assert_ne!(one_way.zobrist_hash(), other_way.zobrist_hash());
+    }
+
+    #[test]
+    fn zobrist_keys_are_distinct_past_the_largest_board_and_deep_stacks() {
+        let dim = MAX_BOARD_DIM as usize + 10;
+        let mut keys = HashSet::new();
+        for row in 0..dim {
+            for col in 0..dim {
+                for player in Player::ALL {
+                    for orbs in 1..=12 {
+                        assert!(keys.insert(zobrist_cell_key(row, col, player, orbs)), "key for ({}, {}, {:?}, {}) repeats", row, col, player, orbs);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn every_animation_frame_has_matching_orb_counts() {
+        // Red's corner sets off a cascade that runs into Blue's bottom row.
+        let mut board = Board::from_ascii(&["1R 2R 1R", "2R 3R 2R", "1B 2B 1B"]).unwrap();
+        board.total_moves = 2;
+
+        let outcome = board.make_move_with_outcome(0, 0).unwrap();
+
+        assert!(outcome.history.len() > 2, "expected a cascade, got {} frames", outcome.history.len());
+        for (index, frame) in outcome.history.iter().enumerate() {
+            assert!(frame.board.orb_counts_match_cells(), "frame {} has orb counts {:?}", index, frame.board.orb_counts);
+        }
+    }
+
+    #[test]
+    fn an_expired_deadline_stops_a_large_cascade() {
+        // Every cell one orb short of exploding, so the first orb sets off the whole board.
+        let mut board = Board::new(MAX_BOARD_DIM, MAX_BOARD_DIM, Player::Red, 2, CriticalMassRule::NeighbourCount, Topology::Grid);
+        for cell in board.cells.iter_mut().flatten() {
+            cell.state = CellState::Occupied { player: Player::Red, orbs: cell.critical_mass - 1 };
+        }
+        board.recalculate_orb_counts();
+        board.rehash();
+        assert!(board.cascade_length(0, 0).unwrap() > 1000);
+
+        let deadline = Instant::now() + std::time::Duration::from_micros(1);
+        assert_eq!(board.clone().make_move_for_simulation(0, 0, Some(&deadline)), Err(MoveError::SimulationTimeout));
+        assert_eq!(board.clone().make_move_for_simulation(0, 0, Some(&Instant::now())), Err(MoveError::SimulationTimeout));
+    }
+
+    fn critical_masses(board: &Board) -> Vec<Vec<u32>> {
+        board.cells.iter().map(|row| row.iter().map(|cell| cell.critical_mass).collect()).collect()
+    }
+
+    #[test]
+    fn degenerate_boards_get_their_real_neighbour_counts() {
+        let new_board = |width, height| Board::new(width, height, Player::Red, 2, CriticalMassRule::NeighbourCount, Topology::Grid);
+        assert_eq!(critical_masses(&new_board(1, 1)), vec![vec![1]]);
+        // One column of five rows, then one row of five columns.
+        assert_eq!(critical_masses(&new_board(1, 5)), vec![vec![1], vec![2], vec![2], vec![2], vec![1]]);
+        assert_eq!(critical_masses(&new_board(5, 1)), vec![vec![1, 2, 2, 2, 1]]);
+    }
+
+    #[test]
+    fn from_ascii_reads_back_the_log_format() {
+        let board = Board::from_ascii(&["2R 0 1B", "0 3G 0"]).unwrap();
+        assert_eq!(board.cells[0][0].state, CellState::Occupied { player: Player::Red, orbs: 2 });
+        assert_eq!(board.cells[1][1].state, CellState::Occupied { player: Player::Green, orbs: 3 });
+        assert_eq!(board.player_count, 3);
+        assert_eq!((board.orb_counts[&Player::Red], board.orb_counts[&Player::Blue], board.orb_counts[&Player::Green]), (2, 1, 3));
+
+        // Everything after to_log_text's header line is one row per board row.
+        let text = board.to_log_text();
+        let rows: Vec<&str> = text.lines().skip(1).collect();
+        let reread = Board::from_ascii(&rows).unwrap();
+        assert_eq!(reread.zobrist_hash(), board.zobrist_hash());
+        assert_eq!(reread.orb_counts, board.orb_counts);
+
+        assert!(Board::from_ascii(&["1R 0", "0"]).is_err());
+        assert!(Board::from_ascii(&["1R 2X"]).is_err());
+        assert!(Board::from_ascii(&[]).is_err());
+    }
+
+    #[test]
+    fn only_cells_touching_an_enemy_are_contested() {
+        let board = Board::from_ascii(&["1R 1R 0", "0 1B 0", "1R 0 1B"]).unwrap();
+        let contested: Vec<(usize, usize)> = (0..3)
+            .flat_map(|row| (0..3).map(move |col| (row, col)))
+            .filter(|&(row, col)| board.is_contested(row, col))
+            .collect();
+        // (0, 1) and the Blue cell below it face each other; the corners only touch empty cells or
+        // their own side, and empty cells are never contested.
+        assert_eq!(contested, [(0, 1), (1, 1)]);
+    }
+
+    #[test]
+    fn mirrored_and_rotated_positions_share_a_canonical_hash() {
+        let played = |moves: &[(usize, usize)]| {
+            let mut board = Board::new(5, 5, Player::Red, 2, CriticalMassRule::NeighbourCount, Topology::Grid);
+            board.apply_moves(moves).unwrap();
+            board
+        };
+        let moves = [(0, 1), (3, 4), (1, 1), (4, 4)];
+        let original = played(&moves);
+        for symmetry in original.symmetries() {
+            let image: Vec<_> = moves.iter().map(|&(row, col)| symmetry.apply(row, col, 5, 5)).collect();
+            assert_eq!(played(&image).canonical_hash(), original.canonical_hash(), "{:?}", symmetry);
+        }
+
+        let different = played(&[(0, 1), (3, 4), (2, 2), (4, 4)]);
+        assert_ne!(different.canonical_hash(), original.canonical_hash());
+    }
+
+    #[test]
+    fn running_out_of_moves_ends_the_game_per_the_rule() {
+        // On a 2x1 board Blue's only cell borders Red's opening orb, so Blue has nowhere to start.
+        for (rule, expected) in [(NoMovesRule::Loss, GameState::Won { winner: Player::Red }), (NoMovesRule::Draw, GameState::Draw)] {
+            let mut board = Board::new(2, 1, Player::Red, 2, CriticalMassRule::NeighbourCount, Topology::Grid);
+            board.opening_rule = OpeningRule::NotAdjacentToOpponent;
+            board.no_moves_rule = rule;
+            board.make_move_for_simulation(0, 0, None).unwrap();
+
+            assert!(board.get_all_valid_moves().is_empty());
+            assert_eq!(board.game_state, expected);
+            assert!(board.check_move(0, 1).is_err());
+        }
+    }
+
+    #[test]
+    fn orb_histogram_counts_cells_by_stack_size() {
+        let board = Board::from_ascii(&["1R 2R 1B", "0 3R 2B", "1R 0 0"]).unwrap();
+        let histogram = board.orb_histogram();
+        // The centre's critical mass of 4 sets the length.
+        assert_eq!(histogram[&Player::Red], [0, 2, 1, 1, 0]);
+        assert_eq!(histogram[&Player::Blue], [0, 1, 1, 0, 0]);
+        assert_eq!(histogram.len(), 2);
+    }
+
+    #[test]
+    fn a_primed_cell_next_to_an_enemy_makes_the_position_loud() {
+        // Red's corner is primed but only borders its own and empty cells.
+        assert!(Board::from_ascii(&["1R 1R 0", "0 0 0", "0 0 1B"]).unwrap().is_quiet());
+        // The same corner next to Blue could capture on the next move.
+        assert!(!Board::from_ascii(&["1R 1B 0", "0 0 0", "0 0 0"]).unwrap().is_quiet());
+        // So does a primed Blue edge cell next to Red's unprimed centre.
+        assert!(!Board::from_ascii(&["0 0 0", "2B 1R 0", "0 0 0"]).unwrap().is_quiet());
+    }
+
+    #[test]
+    fn the_incremental_hash_keeps_up_with_a_cascade() {
+        let mut board = Board::from_ascii(&["1R 2R 2R 1B", "2R 3R 3R 2B", "1R 2R 2R 1B"]).unwrap();
+        board.total_moves = 4;
+        let outcome = board.make_move_with_outcome(0, 0).unwrap();
+        assert!(outcome.explosion_steps > 5, "only {} explosions", outcome.explosion_steps);
+
+        for position in outcome.history.iter().map(|frame| &frame.board).chain([&board]) {
+            assert_eq!(position.hash, position.cell_hash_under(Symmetry::Identity));
+        }
+    }
+
+    #[test]
+    fn a_walled_off_corner_is_sealed_territory() {
+        // Red's L-shaped wall closes off the empty top-left 2x2 from Blue.
+        let board = Board::from_ascii(&["0 0 1R 0", "0 0 1R 0", "1R 1R 1R 0", "0 0 0 1B"]).unwrap();
+        assert_eq!(board.sealed_territory(Player::Red), 4);
+        assert_eq!(board.sealed_territory(Player::Blue), 0);
+
+        // One gap in the wall and Blue can reach all of it.
+        let breached = Board::from_ascii(&["0 0 1R 0", "0 0 0 0", "1R 1R 1R 0", "0 0 0 1B"]).unwrap();
+        assert_eq!(breached.sealed_territory(Player::Red), 0);
+        // Before Blue has placed anything it could still drop an orb anywhere.
+        assert_eq!(Board::from_ascii(&["1R 0", "0 0"]).unwrap().sealed_territory(Player::Red), 0);
+    }
+
+    #[test]
+    fn a_heavily_primed_board_is_more_volatile() {
+        let primed = Board::from_ascii(&["1R 2R 1B", "2R 0 2B", "1R 2B 1B"]).unwrap();
+        let sparse = Board::from_ascii(&["1R 1R 0", "0 0 0", "0 0 1B"]).unwrap();
+        assert_eq!(primed.volatility(), 1.0);
+        // Both single-orb corners are primed, but the edge cell isn't.
+        assert_eq!(sparse.volatility(), 2.0 / 3.0);
+        assert!(primed.volatility() > sparse.volatility());
+        assert_eq!(Board::from_ascii(&["0 0", "0 0"]).unwrap().volatility(), 0.0);
+    }
+
+    #[test]
+    fn a_simulated_move_returns_the_state_it_leaves() {
+        let mut board = Board::new(3, 3, Player::Red, 2, CriticalMassRule::NeighbourCount, Topology::Grid);
+        while !board.is_over() && board.total_moves < 500 {
+            let (row, col) = *board.get_all_valid_moves().last().unwrap();
+            let state = board.make_move_for_simulation(row, col, None).unwrap();
+            assert_eq!(state, board.game_state);
+        }
+        assert!(board.winner().is_some(), "no winner after {} moves", board.total_moves);
+    }
+
+    #[test]
+    fn a_uniform_four_corner_holds_three_orbs() {
+        let mut board = Board::new(4, 4, Player::Red, 2, CriticalMassRule::Uniform(4), Topology::Grid);
+        board.apply_moves(&[(0, 0), (3, 3), (0, 0), (3, 3), (0, 0), (3, 3)]).unwrap();
+        assert_eq!(board.cells[0][0].state, CellState::Occupied { player: Player::Red, orbs: 3 });
+        assert_eq!(board.cells[3][3].state, CellState::Occupied { player: Player::Blue, orbs: 3 });
+
+        board.apply_moves(&[(0, 0)]).unwrap();
+        assert_ne!(board.cells[0][0].state, CellState::Occupied { player: Player::Red, orbs: 4 });
+        assert_eq!(board.cells[0][1].state, CellState::Occupied { player: Player::Red, orbs: 1 });
+        assert_eq!(board.cells[1][0].state, CellState::Occupied { player: Player::Red, orbs: 1 });
+    }
+
+    #[test]
+    fn rejected_moves_say_why() {
+        let mut board = Board::new(3, 3, Player::Red, 2, CriticalMassRule::NeighbourCount, Topology::Grid);
+        board.apply_moves(&[(0, 0), (2, 2)]).unwrap();
+        assert_eq!(board.check_move(3, 0), Err(MoveError::OutOfBounds { row: 3, col: 0 }));
+        assert_eq!(board.check_move(2, 2), Err(MoveError::OpponentCell));
+        assert_eq!(board.make_move_for_simulation(2, 2, None), Err(MoveError::OpponentCell));
+
+        board.game_state = GameState::Won { winner: Player::Blue };
+        assert_eq!(board.check_move(1, 1), Err(MoveError::GameOver));
+        assert_eq!(board.make_move_for_simulation(1, 1, None), Err(MoveError::GameOver));
+    }
+
+    #[test]
+    fn a_torus_corner_explodes_at_four_and_wraps_around() {
+        let mut board = Board::new(4, 4, Player::Red, 2, CriticalMassRule::NeighbourCount, Topology::Torus);
+        assert!(board.cells.iter().flatten().all(|cell| cell.critical_mass == 4));
+        board.apply_moves(&[(0, 0), (2, 2), (0, 0), (2, 2), (0, 0), (2, 1)]).unwrap();
+        assert_eq!(board.cells[0][0].state, CellState::Occupied { player: Player::Red, orbs: 3 });
+
+        board.apply_moves(&[(0, 0)]).unwrap();
+        assert_eq!(board.cells[0][0].state, CellState::Empty);
+        for (row, col) in [(0, 1), (1, 0), (0, 3), (3, 0)] {
+            assert_eq!(board.cells[row][col].state, CellState::Occupied { player: Player::Red, orbs: 1 }, "({}, {})", row, col);
+        }
+    }
+
+    #[test]
+    fn the_third_repetition_is_a_draw() {
+        // Every orb explodes straight off a lone cell, so the board is empty after each move, and with
+        // neither side holding orbs it is Blue to move every time.
+        let mut board = Board::new(1, 1, Player::Red, 2, CriticalMassRule::Uniform(1), Topology::Grid);
+        assert_eq!(board.make_move_for_simulation(0, 0, None), Ok(GameState::Ongoing));
+        assert_eq!(board.make_move_for_simulation(0, 0, None), Ok(GameState::Ongoing));
+        assert_eq!(board.current_turn, Player::Blue);
+
+        assert_eq!(board.make_move_for_simulation(0, 0, None), Ok(GameState::Draw));
+        assert_eq!(board.check_move(0, 0), Err(MoveError::GameOver));
+    }
+
+    #[test]
+    fn own_half_keeps_each_first_orb_on_its_side() {
+        // Nine rows: Red's half is rows 0-4 and Blue's is rows 4-8, sharing the middle row.
+        let mut board = Board::new(6, 9, Player::Red, 2, CriticalMassRule::NeighbourCount, Topology::Grid);
+        board.opening_rule = OpeningRule::OwnHalf;
+        assert_eq!(board.check_move(5, 0), Err(MoveError::OpeningRule(OpeningRule::OwnHalf)));
+        assert!(board.get_all_valid_moves().iter().all(|&(row, _)| row <= 4));
+        board.apply_moves(&[(4, 0)]).unwrap();
+
+        assert_eq!(board.make_move_for_simulation(3, 5, None), Err(MoveError::OpeningRule(OpeningRule::OwnHalf)));
+        assert!(board.get_all_valid_moves().iter().all(|&(row, _)| row >= 4));
+        board.apply_moves(&[(8, 5)]).unwrap();
+
+        // Once both first orbs are down the whole board is open.
+        board.apply_moves(&[(7, 0), (0, 5)]).unwrap();
+    }
+
+    #[test]
+    fn cached_valid_moves_match_a_full_scan() {
+        let mut board = Board::new(5, 5, Player::Red, 2, CriticalMassRule::NeighbourCount, Topology::Grid);
+        board.forced_capture = ForcedCapture(true);
+        for ply in 0..16 {
+            if board.game_state != GameState::Ongoing { break; }
+            let moves = board.get_all_valid_moves();
+            let (row, col) = moves[ply * 7 % moves.len()];
+            board.apply_moves(&[(row, col)]).unwrap();
+            assert!(board.valid_moves.is_some());
+            assert_eq!(board.get_all_valid_moves(), board.scan_valid_moves());
+        }
+        assert!(board.total_moves >= 8);
+    }
+
+    #[test]
+    fn apply_moves_reports_the_index_of_the_first_illegal_move() {
+        let mut board = Board::new(3, 3, Player::Red, 2, CriticalMassRule::NeighbourCount, Topology::Grid);
+        assert_eq!(board.apply_moves(&[(0, 0), (2, 2), (1, 1), (1, 1), (0, 1)]), Err((3, MoveError::OpponentCell)));
+        // The three moves before the illegal one stay on the board and the rest are never tried.
+        assert_eq!(board.total_moves, 3);
+        assert_eq!(board.current_turn, Player::Blue);
+        assert_eq!(board.cells[0][1].state, CellState::Empty);
+        assert_eq!(board.apply_moves(&[]), Ok(()));
+    }
+
+    #[test]
+    fn an_empty_board_keeps_one_move_per_symmetry_class() {
+        // A square has eight symmetries, leaving the triangle r <= c of one 3x3 quadrant.
+        let board = Board::new(6, 6, Player::Red, 2, CriticalMassRule::NeighbourCount, Topology::Grid);
+        assert_eq!(board.canonical_moves(), vec![(0, 0), (0, 1), (0, 2), (1, 1), (1, 2), (2, 2)]);
+
+        // A 6x9 rectangle only has its two mirrors and half-turn: a quadrant of 5 rows by 3 columns.
+        let board = Board::new(6, 9, Player::Red, 2, CriticalMassRule::NeighbourCount, Topology::Grid);
+        assert_eq!(board.canonical_moves().len(), 15);
+
+        // One orb off centre breaks every symmetry.
+        let mut board = Board::new(6, 6, Player::Red, 2, CriticalMassRule::NeighbourCount, Topology::Grid);
+        board.apply_moves(&[(0, 1)]).unwrap();
+        assert_eq!(board.canonical_moves(), board.get_all_valid_moves());
+    }
+
+    #[test]
+    fn orb_counts_keep_up_with_a_cascade() {
+        let mut board = Board::from_ascii(&["1R 2R 2R 1B", "2R 3R 3R 2B", "1R 2R 2R 1B"]).unwrap();
+        board.total_moves = 4;
+        let outcome = board.make_move_with_outcome(0, 0).unwrap();
+        assert!(outcome.explosion_steps > 5, "only {} explosions", outcome.explosion_steps);
+
+        for position in outcome.history.iter().map(|frame| &frame.board).chain([&board]) {
+            assert!(position.orb_counts_match_cells(), "{}", position);
+        }
+        // Every orb on the board, plus the one just played, ends up Red's.
+        assert_eq!(board.orb_counts[&Player::Red], 23);
+        assert_eq!(board.orb_counts[&Player::Blue], 0);
+    }
+
+    #[test]
+    fn display_renders_the_header_and_grid() {
+        let mut board = Board::new(3, 2, Player::Red, 2, CriticalMassRule::NeighbourCount, Topology::Grid);
+        board.apply_moves(&[(0, 1), (1, 2), (0, 1)]).unwrap();
+        assert_eq!(board.to_string(), "--- Turn: Blue | Game: Ongoing | Orbs: R-2 B-1 ---\n[ ] [2R] [ ] \n[ ] [ ] [1B] \n");
+    }
+
+    #[test]
+    fn winner_and_is_over_follow_the_game_state() {
+        let mut board = Board::from_ascii(&["1R 1B 0", "0 0 0", "0 0 0"]).unwrap();
+        board.total_moves = 2;
+        assert_eq!((board.winner(), board.is_over()), (None, false));
+
+        // Red's corner explodes onto Blue's only cell.
+        board.apply_moves(&[(0, 0)]).unwrap();
+        assert_eq!((board.winner(), board.is_over()), (Some(Player::Red), true));
+
+        board.game_state = GameState::Draw;
+        assert_eq!((board.winner(), board.is_over()), (None, true));
+    }
+
+    #[test]
+    fn fog_of_war_hides_cells_and_their_orbs() {
+        let board = Board::from_ascii(&["2R 1R 0 0", "0 1B 0 0", "0 0 0 2B", "0 0 0 1B"]).unwrap();
+        let owned_by = |player: Player| -> Vec<(usize, usize)> {
+            (0..4).flat_map(|r| (0..4).map(move |c| (r, c)))
+                .filter(|&(r, c)| matches!(board.cells[r][c].state, CellState::Occupied { player: owner, .. } if owner == player))
+                .collect()
+        };
+        // Any Red cell and Blue cell side by side see each other; no one sees an enemy cell from afar.
+        for (rr, rc) in owned_by(Player::Red) {
+            for (br, bc) in owned_by(Player::Blue) {
+                let touching = board.neighbors(rr, rc).contains(&(br, bc));
+                assert!(!touching || (board.is_visible_to(Player::Red, br, bc) && board.is_visible_to(Player::Blue, rr, rc)));
+            }
+        }
+        let seen_by = |viewer: Player, other: Player| owned_by(other).into_iter()
+            .filter(|&(r, c)| board.is_visible_to(viewer, r, c))
+            .collect::<Vec<_>>();
+        assert_eq!(seen_by(Player::Red, Player::Blue), vec![(1, 1)]);
+        assert_eq!(seen_by(Player::Blue, Player::Red), vec![(0, 1)]);
+
+        // The hidden cells' orbs are missing from each view's counts too.
+        let red_view = board.visible_to(Player::Red);
+        assert_eq!(red_view.cells[2][3].state, CellState::Empty);
+        assert_eq!(red_view.orb_counts[&Player::Red], 3);
+        assert_eq!(red_view.orb_counts[&Player::Blue], 1);
+        let blue_view = board.visible_to(Player::Blue);
+        assert_eq!(blue_view.cells[0][0].state, CellState::Empty);
+        assert_eq!(blue_view.orb_counts[&Player::Red], 1);
+        assert_eq!(blue_view.orb_counts[&Player::Blue], 4);
+        assert!(blue_view.previous.is_none());
+    }
+
+    #[test]
+    fn forced_capture_excludes_moves_that_capture_nothing() {
+        // Red's corner at (0, 0) explodes into Blue's cell at (0, 1); Red's centre orb is three short.
+        let mut board = Board::new(3, 3, Player::Red, 2, CriticalMassRule::NeighbourCount, Topology::Grid);
+        board.apply_moves(&[(0, 0), (0, 1), (1, 1), (2, 2)]).unwrap();
+        assert_eq!(board.get_all_valid_moves().len(), 7);
+
+        // Switching the rule on after the moves were listed must not serve the stale list.
+        board.forced_capture = ForcedCapture(true);
+        assert_eq!(board.get_all_valid_moves(), vec![(0, 0)]);
+        assert_eq!(board.check_move(1, 1), Err(MoveError::MustCapture));
+        assert_eq!(board.check_move(1, 2), Err(MoveError::MustCapture));
+        board.forced_capture = ForcedCapture(false);
+        assert_eq!(board.get_all_valid_moves().len(), 7);
+
+        // With nothing to capture every move is allowed again.
+        let mut quiet = Board::new(3, 3, Player::Red, 2, CriticalMassRule::NeighbourCount, Topology::Grid);
+        quiet.apply_moves(&[(0, 0), (2, 2)]).unwrap();
+        quiet.forced_capture = ForcedCapture(true);
+        assert_eq!(quiet.get_all_valid_moves().len(), 8);
+    }
+
+    #[test]
+    fn changing_the_opening_rule_mid_opening_relists_the_moves() {
+        let mut board = Board::new(6, 9, Player::Red, 2, CriticalMassRule::NeighbourCount, Topology::Grid);
+        board.apply_moves(&[(0, 0)]).unwrap();
+        assert_eq!(board.get_all_valid_moves().len(), 53);
+
+        board.opening_rule = OpeningRule::OwnHalf;
+        assert!(board.get_all_valid_moves().iter().all(|&(row, _)| row >= 4));
+        assert_eq!(board.check_move(0, 5), Err(MoveError::OpeningRule(OpeningRule::OwnHalf)));
+    }
+
+    #[test]
+    fn dimensions_outside_the_limits_are_rejected() {
+        assert!(Board::validate_dimensions(0, 5).is_err_and(|e| e.contains("width")));
+        assert!(Board::validate_dimensions(5, 0).is_err_and(|e| e.contains("height")));
+        assert!(Board::validate_dimensions(MAX_BOARD_DIM + 1, 5).is_err());
+        assert!(Board::validate_dimensions(5, MAX_BOARD_DIM + 1).is_err());
+
+        for (width, height) in [(1, 1), (1, 5), (5, 1), (MAX_BOARD_DIM, MAX_BOARD_DIM)] {
+            assert_eq!(Board::validate_dimensions(width, height), Ok(()), "{}x{}", width, height);
+        }
+    }
+}
@@ -0,0 +1,165 @@
+use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Player {
+    Red,
+    Blue,
+    Green,
+    Yellow,
+}
+
+impl Player {
+    // Every seat in turn order; a game with n players uses the first n.
+    pub const ALL: [Player; 4] = [Player::Red, Player::Blue, Player::Green, Player::Yellow];
+
+    // Position in `ALL`.
+    pub fn index(self) -> usize {
+        match self {
+            Player::Red => 0,
+            Player::Blue => 1,
+            Player::Green => 2,
+            Player::Yellow => 3,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum CellState {
+    Empty,
+    Occupied { player: Player, orbs: u32 },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum GameState {
+    Ongoing,
+    Won { winner: Player },
+    Draw,
+}
+
+// A finished game's summary for the post-game stats screen. `winner` is None for a draw.
+#[derive(Debug, Clone, Serialize)]
+pub struct GameResult {
+    pub winner: Option<Player>,
+    pub total_moves: u32,
+    pub final_orbs: HashMap<Player, u32>,
+    pub duration_ms: u64,
+}
+
+// What happens when the side to move has no legal cell left to play (e.g. the opponent owns every cell).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum NoMovesRule {
+    Loss,
+    Draw,
+}
+
+// Where each player may put their first orb.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum OpeningRule {
+    // Standard rules: the first orb can go on any empty cell.
+    #[default]
+    Anywhere,
+    // Not on or next to a cell another player already holds.
+    NotAdjacentToOpponent,
+    // Red (and Green) in the top half of the board, Blue (and Yellow) in the bottom half. The middle
+    // row of an odd-height board belongs to both.
+    OwnHalf,
+}
+
+// The "must capture" variant: when on, a side that can take opponent orbs with a move has to play one
+// of those moves. Off in standard rules.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct ForcedCapture(pub bool);
+
+// How the board's edges connect.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Topology {
+    // Ordinary board: edge and corner cells have fewer neighbours.
+    #[default]
+    Grid,
+    // Left edge joins the right and top joins bottom, so every cell has four neighbours.
+    Torus,
+}
+
+// How each cell's critical mass is chosen when a board is built.
+#[derive(Debug, Clone, Copy)]
+pub enum CriticalMassRule {
+    // The number of orthogonal neighbours: 2 in corners, 3 on edges, 4 inside.
+    NeighbourCount,
+    // The same value for every cell.
+    Uniform(u32),
+    // Called with (row, col, neighbour count) for every cell.
+    Custom(fn(usize, usize, u32) -> u32),
+}
+
+impl CriticalMassRule {
+    // Never below 1, so every cell can eventually explode.
+    pub fn critical_mass(self, row: usize, col: usize, neighbours: u32) -> u32 {
+        let mass = match self {
+            CriticalMassRule::NeighbourCount => neighbours,
+            CriticalMassRule::Uniform(mass) => mass,
+            CriticalMassRule::Custom(rule) => rule(row, col, neighbours),
+        };
+        mass.max(1)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Cell {
+    pub state: CellState,
+    pub critical_mass: u32,
+    pub is_queued: bool,
+}
+
+impl Cell {
+    pub fn new(critical_mass: u32) -> Self {
+        Cell {
+            state: CellState::Empty,
+            critical_mass,
+            is_queued: false,
+        }
+    }
+
+    // True when one more orb (from whichever player) would make this cell explode.
+    pub fn explodes_on_next_orb(&self) -> bool {
+        let orbs = match self.state {
+            CellState::Occupied { orbs, .. } => orbs,
+            CellState::Empty => 0,
+        };
+        orbs + 1 >= self.critical_mass
+    }
+
+    pub fn add_orb(&mut self, player: Player) -> bool {
+        match self.state {
+            CellState::Empty => {
+                self.state = CellState::Occupied { player, orbs: 1 };
+                true
+            }
+            CellState::Occupied { player: p, orbs } => {
+                if p == player {
+                    self.state = CellState::Occupied { player, orbs: orbs + 1 };
+                    true
+                } else {
+                    false
+                }
+            }
+        }
+    }
+    
+    pub fn get_explosion_data(&self) -> Option<(Player, u32)> {
+        if let CellState::Occupied { player, orbs } = self.state {
+            if orbs >= self.critical_mass {
+                return Some((player, orbs));
+            }
+        }
+        None
+    }
+
+    pub fn take_over(&mut self, player: Player) {
+        let orbs = match self.state {
+            CellState::Occupied { orbs, .. } => orbs,
+            CellState::Empty => 0,
+        };
+        self.state = CellState::Occupied { player, orbs: orbs + 1 };
+    }
+}
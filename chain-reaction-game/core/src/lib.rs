@@ -0,0 +1,7 @@
+// The game rules and AI, kept free of the filesystem and Tauri so the same code runs in the desktop
+// app and in the browser (wasm32-unknown-unknown). Moves are reported through `board::MoveLogger`
+// rather than written to disk here.
+pub mod game;
+pub mod board;
+pub mod ai;
+pub mod selfplay;
@@ -0,0 +1,169 @@
+// Headless AI-vs-AI games for tournaments and self-play data generation.
+// Nothing here touches the log file; every move goes through the simulation path.
+
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+use crate::ai::{self, AIStrategy, EvalOptions, Heuristic, HeuristicWeights};
+use crate::board::Board;
+use crate::game::{CriticalMassRule, Player, Topology};
+
+// Upper bound on game length so two passive AIs can't stall a batch.
+const SELF_PLAY_MOVE_CAP: usize = 1000;
+
+// Weight optimisation plays a batch of games per candidate, so both loops are capped.
+pub const OPTIMIZER_MAX_ITERATIONS: u32 = 50;
+pub const OPTIMIZER_MAX_GAMES_PER_EVAL: u32 = 20;
+// Each step scales one weight up or down by this fraction.
+const OPTIMIZER_STEP: f64 = 0.5;
+
+#[derive(Debug, Clone)]
+pub struct AIPlayerConfig {
+    pub strategy: AIStrategy,
+    pub heuristics: Vec<Heuristic>,
+    pub eval_options: EvalOptions,
+    pub depth: u32,
+    pub time_limit_ms: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct SelfPlayParams {
+    pub width: u32,
+    pub height: u32,
+    pub games: u32,
+    pub seed: u64,
+    // The first plies of every game are played at random (seeded) before the AIs take over,
+    // so a single pair of configs still produces varied games.
+    pub random_opening_plies: u32,
+}
+
+#[derive(Debug, Clone)]
+pub struct GameRecord {
+    pub moves: Vec<(usize, usize)>,
+    pub winner: Option<Player>,
+}
+
+impl GameRecord {
+    // A hash of the move sequence; two games share a fingerprint only if they were played identically.
+    pub fn fingerprint(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.moves.hash(&mut hasher);
+        hasher.finish()
+    }
+}
+
+/// Plays one game between `red` and `blue`, with the random opening driven by `seed`.
+pub fn play_game(red: &AIPlayerConfig, blue: &AIPlayerConfig, params: &SelfPlayParams, seed: u64) -> GameRecord {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut board = Board::new(params.width, params.height, Player::Red, 2, CriticalMassRule::NeighbourCount, Topology::Grid);
+    let mut moves = Vec::new();
+
+    while !board.is_over() && moves.len() < SELF_PLAY_MOVE_CAP {
+        let next_move = if moves.len() < params.random_opening_plies as usize {
+            ai::random_move(&board, &mut rng)
+        } else {
+            let player = if board.current_turn == Player::Red { red } else { blue };
+            // Random AIs draw from the game's seeded stream so the whole game is reproducible.
+            ai::get_ai_move_with_rng(&board, player.strategy, &player.heuristics, player.eval_options, player.depth, player.time_limit_ms, &mut rng)
+                .map(|stats| stats.chosen)
+        };
+
+        let Some((row, col)) = next_move else { break; };
+        if board.make_move_for_simulation(row, col, None).is_err() { break; }
+        moves.push((row, col));
+    }
+
+    GameRecord { moves, winner: board.winner() }
+}
+
+/// Plays `params.games` games in a row, deriving each game's seed from `params.seed`.
+pub fn run_self_play(red: &AIPlayerConfig, blue: &AIPlayerConfig, params: &SelfPlayParams) -> Vec<GameRecord> {
+    (0..params.games)
+        .map(|i| play_game(red, blue, params, params.seed.wrapping_add(i as u64)))
+        .collect()
+}
+
+/// The fraction of games `candidate` wins against `opponent`. Colours alternate game by game and
+/// game `i` always uses seed `params.seed + i`, so two candidates are compared on the same openings.
+pub fn win_rate(candidate: &AIPlayerConfig, opponent: &AIPlayerConfig, params: &SelfPlayParams) -> f64 {
+    let games = params.games.clamp(1, OPTIMIZER_MAX_GAMES_PER_EVAL);
+    let wins = (0..games).filter(|&i| {
+        let seed = params.seed.wrapping_add(i as u64);
+        if i % 2 == 0 {
+            play_game(candidate, opponent, params, seed).winner == Some(Player::Red)
+        } else {
+            play_game(opponent, candidate, params, seed).winner == Some(Player::Blue)
+        }
+    }).count();
+    wins as f64 / games as f64
+}
+
+/// Coordinate ascent on `base`'s weights: each iteration nudges the next enabled heuristic's weight
+/// up, then down, and keeps the first change that strictly raises the win rate against `opponent`.
+/// Since only improvements are accepted the result never does worse than `base` on these games.
+pub fn optimize_weights(base: &AIPlayerConfig, opponent: &AIPlayerConfig, params: &SelfPlayParams, iterations: u32) -> HeuristicWeights {
+    let mut best = base.clone();
+    if base.heuristics.is_empty() { return best.eval_options.weights; }
+    let mut best_rate = win_rate(&best, opponent, params);
+
+    for i in 0..iterations.min(OPTIMIZER_MAX_ITERATIONS) {
+        let heuristic = base.heuristics[i as usize % base.heuristics.len()];
+        for factor in [1.0 + OPTIMIZER_STEP, 1.0 - OPTIMIZER_STEP] {
+            let mut candidate = best.clone();
+            *candidate.eval_options.weights.get_mut(heuristic) *= factor;
+            let rate = win_rate(&candidate, opponent, params);
+            if rate > best_rate {
+                best = candidate;
+                best_rate = rate;
+                break;
+            }
+        }
+    }
+    best.eval_options.weights
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn random_openings_diversify_a_batch() {
+        // A one-ply search with fixed tie-breaking plays the same game every time on its own.
+        let ai = AIPlayerConfig {
+            strategy: AIStrategy::AlphaBeta,
+            heuristics: vec![Heuristic::OrbDifference, Heuristic::CenterControl],
+            eval_options: EvalOptions::default(),
+            depth: 1,
+            time_limit_ms: 60_000,
+        };
+        let distinct_games = |random_opening_plies| {
+            let params = SelfPlayParams { width: 4, height: 4, games: 8, seed: 11, random_opening_plies };
+            run_self_play(&ai, &ai, &params).iter().map(GameRecord::fingerprint).collect::<HashSet<_>>().len()
+        };
+
+        let counts: Vec<usize> = [0, 1, 4].into_iter().map(distinct_games).collect();
+        assert_eq!(counts[0], 1);
+        assert!(counts[0] < counts[1] && counts[1] <= counts[2], "{:?}", counts);
+    }
+
+    #[test]
+    fn optimized_weights_never_lose_to_the_starting_weights() {
+        let base = AIPlayerConfig {
+            strategy: AIStrategy::AlphaBeta,
+            heuristics: vec![Heuristic::OrbDifference, Heuristic::CenterControl],
+            eval_options: EvalOptions::default(),
+            depth: 1,
+            time_limit_ms: 60_000,
+        };
+        let opponent = AIPlayerConfig { strategy: AIStrategy::Random, ..base.clone() };
+        let params = SelfPlayParams { width: 3, height: 3, games: 4, seed: 5, random_opening_plies: 2 };
+
+        let weights = optimize_weights(&base, &opponent, &params, 4);
+        assert_eq!(weights, weights.clamped());
+        let tuned = AIPlayerConfig { eval_options: EvalOptions { weights, ..base.eval_options }, ..base.clone() };
+        assert!(win_rate(&tuned, &opponent, &params) >= win_rate(&base, &opponent, &params));
+    }
+}
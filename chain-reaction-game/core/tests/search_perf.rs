@@ -0,0 +1,56 @@
+// Performance tripwire for the alpha-beta search. Searches a fixed 6x9 midgame position to depth 3
+// and fails if it takes longer than the (deliberately generous) bound, so an accidental blowup in
+// move generation or cascade handling shows up as a failed run.
+//
+// It is ignored by default since the bound only means something in release mode:
+//     cargo test --release --test search_perf -- --ignored --nocapture
+
+use std::time::Instant;
+
+use chain_reaction_core::ai::{self, AIStrategy, EvalOptions, Heuristic};
+use chain_reaction_core::board::Board;
+use chain_reaction_core::game::{CriticalMassRule, Player, Topology};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+const POSITION_SEED: u64 = 0xC4A1_5EED;
+const OPENING_PLIES: u32 = 30;
+const SEARCH_DEPTH: u32 = 3;
+const TIME_BOUND_MS: u128 = 10_000;
+
+const HEURISTICS: [Heuristic; 7] = [
+    Heuristic::OrbDifference, Heuristic::PeripheralControl, Heuristic::TerritoryControl,
+    Heuristic::ChainReactionPotential, Heuristic::ConversionPotential, Heuristic::CascadePotential,
+    Heuristic::SafeMobility,
+];
+
+// Builds the same midgame position on every run by replaying seeded random moves.
+fn midgame_position() -> Board {
+    let mut board = Board::new(6, 9, Player::Red, 2, CriticalMassRule::NeighbourCount, Topology::Grid);
+    let mut rng = StdRng::seed_from_u64(POSITION_SEED);
+    for _ in 0..OPENING_PLIES {
+        if board.is_over() { break; }
+        let Some((row, col)) = ai::random_move(&board, &mut rng) else { break; };
+        board.make_move_for_simulation(row, col, None).expect("random_move only returns legal moves");
+    }
+    board
+}
+
+#[test]
+#[ignore = "timing bound is only meaningful in release mode"]
+fn depth_three_search_stays_under_the_bound() {
+    let board = midgame_position();
+
+    let start = Instant::now();
+    // The search's own time limit sits far above the bound so it always completes the full depth.
+    let stats = ai::get_ai_move(&board, AIStrategy::AlphaBeta, &HEURISTICS, EvalOptions::default(), SEARCH_DEPTH, (TIME_BOUND_MS * 10) as u64)
+        .expect("the midgame position has legal moves");
+    let elapsed = start.elapsed().as_millis();
+
+    println!(
+        "depth {} search on move {} chose {:?} after {} nodes in {} ms (bound {} ms)",
+        SEARCH_DEPTH, board.total_moves, stats.chosen, stats.nodes, elapsed, TIME_BOUND_MS
+    );
+    assert_eq!(stats.depth_reached, SEARCH_DEPTH);
+    assert!(elapsed <= TIME_BOUND_MS, "search took {} ms, over the {} ms bound", elapsed, TIME_BOUND_MS);
+}
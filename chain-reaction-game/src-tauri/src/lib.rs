@@ -1,17 +1,24 @@
 // Prevents additional console window on Windows in release, DO NOT REMOVE!!
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
-use std::sync::Mutex;
-use tauri::{State, AppHandle}; 
+use std::borrow::Cow;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+use tauri::{State, AppHandle, Emitter, Manager}; 
 use serde::{Deserialize, Serialize};
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
 
-pub mod game;
-pub mod board;
-pub mod ai;
+pub use chain_reaction_core::{ai, board, game, selfplay};
+mod move_log;
 
-use board::Board; 
-use game::{Player, CellState};
-use ai::{get_ai_move, AIStrategy, Heuristic};
+use board::{parse_log_header, Board, Frame, MoveError, MoveLogger, MoveOutcome}; 
+use game::{GameResult, Player, CriticalMassRule, ForcedCapture, NoMovesRule, OpeningRule, Topology};
+use ai::{get_ai_move, AICaches, AIStrategy, EvalOptions, Heuristic, HeuristicWeights, MoveClassification, SearchParams, SearchStats, TieBreak};
+use selfplay::{AIPlayerConfig, SelfPlayParams};
+use move_log::FileMoveLogger;
 
 // --- Data Transfer Objects (DTOs) ---
 // These DTOs are the contract between Rust and the Svelte frontend.
@@ -20,6 +27,25 @@ pub struct CellData {
     pub player: Option<String>,
     pub orbs: u32,
     pub critical_mass: u32,
+    #[serde(default)]
+    pub contested: bool,
+    // orbs / critical_mass, so the frontend can draw the fill level directly.
+    #[serde(default)]
+    pub capacity_fraction: f64,
+    // One more orb here sets off an explosion.
+    #[serde(default)]
+    pub will_explode_next: bool,
+    // Out of the viewer's sight in a fog-of-war game; the cell is sent as empty.
+    #[serde(default)]
+    pub hidden: bool,
+}
+
+// Payload of the "chain-step" event: one frame of a move's animation. `sequence` counts from 0
+// within the move, so the frontend can tell frames apart.
+#[derive(Debug, Clone, Serialize)]
+pub struct ChainStepEvent {
+    pub sequence: usize,
+    pub state: GameStateData,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -30,35 +56,308 @@ pub struct GameStateData {
     pub winner: Option<String>,
     pub red_orbs: u32,
     pub blue_orbs: u32,
+    // Zero in two-player games.
+    #[serde(default)]
+    pub green_orbs: u32,
+    #[serde(default)]
+    pub yellow_orbs: u32,
     pub total_moves: u32,
+    // Incremented by every start_game; echo it back to make_move so stale calls are rejected.
+    #[serde(default)]
+    pub generation: u64,
+    // In animation frames, the cells whose explosions produced this frame; empty otherwise.
+    #[serde(default)]
+    pub exploded: Vec<(usize, usize)>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MoveOutcomeData {
+    pub history: Vec<GameStateData>,
+    pub explosion_steps: usize,
+    pub red_delta: i32,
+    pub blue_delta: i32,
+    pub green_delta: i32,
+    pub yellow_delta: i32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct AIConfigData {
     pub strategy: String,
     pub depth: u32,
     pub heuristics: Vec<String>,
     pub time_limit_ms: u64,
+    #[serde(default)]
+    pub zero_sum: bool,
+    // Per-heuristic multipliers; omitted fields keep their defaults.
+    #[serde(default)]
+    pub weights: HeuristicWeights,
+    // Seeds this AI's own random stream for the game, which takes precedence over the game seed.
+    #[serde(default)]
+    pub seed: Option<u64>,
+    // The AI concedes a two-player game once its best search score drops below this. Off when
+    // omitted, e.g. for tournament play.
+    #[serde(default)]
+    pub resign_threshold: Option<f64>,
+    // How alpha-beta picks among equally scored moves: "First" (the default), "NearCorner", "Shallow"
+    // or "Random".
+    #[serde(default)]
+    pub tie_break: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct PlayerConfigData {
     pub player_type: String,
     pub name: String,
     pub ai_config: Option<AIConfigData>,
+    // "Easy", "Medium", "Hard" or "Expert": a preset used when `ai_config` is left out.
+    #[serde(default)]
+    pub difficulty: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl PlayerConfigData {
+    // The explicit `ai_config`, or else the preset for `difficulty`.
+    pub fn resolved_ai_config(&self) -> Option<Cow<'_, AIConfigData>> {
+        match (&self.ai_config, self.difficulty.as_deref().and_then(Difficulty::parse)) {
+            (Some(ai_conf), _) => Some(Cow::Borrowed(ai_conf)),
+            (None, Some(difficulty)) => Some(Cow::Owned(ai_config_for(difficulty))),
+            (None, None) => None,
+        }
+    }
+}
+
+// AI levels for players who'd rather not pick a strategy, depth and heuristics themselves.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Difficulty {
+    Easy,
+    Medium,
+    Hard,
+    Expert,
+}
+
+impl Difficulty {
+    fn parse(name: &str) -> Option<Difficulty> {
+        match name {
+            "Easy" => Some(Difficulty::Easy),
+            "Medium" => Some(Difficulty::Medium),
+            "Hard" => Some(Difficulty::Hard),
+            "Expert" => Some(Difficulty::Expert),
+            _ => None,
+        }
+    }
+}
+
+// The AI settings behind each difficulty, from random play up to a deep search on every heuristic.
+pub fn ai_config_for(difficulty: Difficulty) -> AIConfigData {
+    let (strategy, depth, heuristics, time_limit_ms): (&str, u32, &[&str], u64) = match difficulty {
+        Difficulty::Easy => ("Random", 1, &[], 1000),
+        Difficulty::Medium => ("AlphaBeta", 2, &["OrbDifference", "CascadePotential"], 1000),
+        Difficulty::Hard => ("AlphaBeta", 3, &["OrbDifference", "CascadePotential", "ConversionPotential", "SafeMobility"], 2000),
+        Difficulty::Expert => ("AlphaBeta", 5, &[
+            "OrbDifference", "PeripheralControl", "TerritoryControl", "ChainReactionPotential",
+            "ConversionPotential", "CascadePotential", "SafeMobility", "Mobility", "Influence", "Vulnerability",
+        ], 3000),
+    };
+    AIConfigData {
+        strategy: strategy.to_string(),
+        depth,
+        heuristics: heuristics.iter().map(|h| h.to_string()).collect(),
+        time_limit_ms,
+        zero_sum: false,
+        weights: HeuristicWeights::default(),
+        seed: None,
+        resign_threshold: None,
+        tie_break: None,
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct GameConfigData {
     pub width: u32,
     pub height: u32,
     pub red_player: PlayerConfigData,
     pub blue_player: PlayerConfigData,
+    // "Loss" (default) or "Draw": the outcome when the side to move has no legal moves.
+    #[serde(default)]
+    pub no_moves_rule: Option<String>,
+    // When set, every random choice made for this game (Random AI moves, rollouts) is derived from it.
+    #[serde(default)]
+    pub game_seed: Option<u64>,
+    // 2 (default) to 4. Green and Yellow take the third and fourth seats and need their own configs.
+    #[serde(default)]
+    pub player_count: Option<u32>,
+    #[serde(default)]
+    pub green_player: Option<PlayerConfigData>,
+    #[serde(default)]
+    pub yellow_player: Option<PlayerConfigData>,
+    // "Grid" (default) or "Torus": whether the board's edges wrap around.
+    #[serde(default)]
+    pub topology: Option<String>,
+    // "Anywhere" (default), "NotAdjacentToOpponent" or "OwnHalf": where each player's first orb may go.
+    #[serde(default)]
+    pub opening_rule: Option<String>,
+    // The "must capture" variant: a move that takes opponent orbs has to be played when one exists.
+    #[serde(default)]
+    pub forced_capture: bool,
+    // Each player only sees their own cells and the cells next to them; see `get_current_state`.
+    #[serde(default)]
+    pub fog_of_war: bool,
+}
+
+impl GameConfigData {
+    pub fn player_count(&self) -> usize {
+        self.player_count.unwrap_or(2) as usize
+    }
+
+    pub fn topology(&self) -> Topology {
+        match self.topology.as_deref() {
+            Some("Torus") => Topology::Torus,
+            _ => Topology::Grid,
+        }
+    }
+
+    pub fn opening_rule(&self) -> OpeningRule {
+        match self.opening_rule.as_deref() {
+            Some("NotAdjacentToOpponent") => OpeningRule::NotAdjacentToOpponent,
+            Some("OwnHalf") => OpeningRule::OwnHalf,
+            _ => OpeningRule::Anywhere,
+        }
+    }
+
+    // The config for `player`'s seat, or None for a seat this game doesn't use.
+    pub fn player_config(&self, player: Player) -> Option<&PlayerConfigData> {
+        match player {
+            Player::Red => Some(&self.red_player),
+            Player::Blue => Some(&self.blue_player),
+            Player::Green => self.green_player.as_ref(),
+            Player::Yellow => self.yellow_player.as_ref(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SelfPlayConfigData {
+    pub width: u32,
+    pub height: u32,
+    pub red_ai: AIConfigData,
+    pub blue_ai: AIConfigData,
+    pub games: u32,
+    pub seed: u64,
+    #[serde(default)]
+    pub random_opening_plies: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SelfPlayGameData {
+    pub winner: Option<String>,
+    pub total_moves: u32,
+    pub fingerprint: u64,
+}
+
+// Why `make_move` rejected a move, for the UI's error toast. `kind` names the `MoveError` variant, or
+// is "Other" for anything else (no game started, a stale game). `owner` and `orbs` describe an
+// opponent's cell that was clicked; `width` and `height` the board a move fell off.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct MoveErrorData {
+    pub kind: String,
+    pub message: String,
+    pub owner: Option<String>,
+    pub orbs: Option<u32>,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+}
+
+impl MoveErrorData {
+    fn from_move_error(error: MoveError, board: &Board, row: usize, col: usize) -> Self {
+        let kind = match error {
+            MoveError::GameOver => "GameOver",
+            MoveError::OutOfBounds { .. } => "OutOfBounds",
+            MoveError::OpponentCell => "OpponentCell",
+            MoveError::OpeningRule(_) => "OpeningRule",
+            MoveError::MustCapture => "MustCapture",
+            MoveError::SimulationTimeout => "SimulationTimeout",
+        };
+        let mut data = MoveErrorData { kind: kind.to_string(), message: error.to_string(), ..Default::default() };
+        match error {
+            MoveError::OpponentCell => {
+                if let game::CellState::Occupied { player, orbs } = board.cells[row][col].state {
+                    data.message = format!("{} Cell ({}, {}) belongs to {:?} and holds {} orb(s).", data.message, row, col, player, orbs);
+                    data.owner = Some(format!("{:?}", player));
+                    data.orbs = Some(orbs);
+                }
+            }
+            MoveError::OutOfBounds { .. } => {
+                data.message = format!("{} The board is {} wide and {} tall.", data.message, board.width, board.height);
+                data.width = Some(board.width);
+                data.height = Some(board.height);
+            }
+            _ => {}
+        }
+        data
+    }
+}
+
+impl From<String> for MoveErrorData {
+    fn from(message: String) -> Self {
+        MoveErrorData { kind: "Other".to_string(), message, ..Default::default() }
+    }
+}
+
+// Called after every move applied through the manager with the mover, the cell played and the resulting board.
+pub type MoveObserver = Box<dyn FnMut(Player, (usize, usize), &Board) + Send>;
+
+// An alpha-beta search for the AI to move, started on a background thread as soon as the move before
+// it landed. The thread owns copies of the board, config and the seat's RNG stream, so it never
+// touches the manager's lock; the stream comes back with the result, advanced as the search left it.
+struct Ponder {
+    // `zobrist_hash()` and game generation of the position being searched.
+    hash: u64,
+    generation: u64,
+    handle: JoinHandle<(Result<SearchStats, String>, Option<StdRng>)>,
 }
 
 pub struct GameManager {
     pub board: Option<Board>,
     pub config: Option<GameConfigData>,
+    pub ai_caches: AICaches,
+    pub generation: u64,
+    // Every move applied since start_game, in order.
+    pub move_history: Vec<(usize, usize)>,
+    // One stream per AI seat that has its own `seed`, advanced by each of that AI's random moves.
+    ai_rngs: HashMap<Player, StdRng>,
+    last_start: Option<Instant>,
+    move_observer: Option<MoveObserver>,
+    // Off by default; see `set_pondering`.
+    ponder_enabled: bool,
+    ponder: Option<Ponder>,
+    // Set while `get_ai_move_command` searches off the lock; moves and undos are refused meanwhile.
+    ai_thinking: bool,
+    // The settled position after each move, oldest first and numbered by `total_moves`, for spectators
+    // scrubbing through the game. Only the latest SNAPSHOT_LIMIT are kept.
+    snapshots: VecDeque<Board>,
+}
+
+const SNAPSHOT_LIMIT: usize = 1000;
+
+// Everything an AI search needs, lent out of the manager so the search can run without holding its
+// lock. `finish_ai_search` takes it back.
+struct AISearch {
+    board: Board,
+    config: GameConfigData,
+    generation: u64,
+    caches: AICaches,
+    rng: Option<StdRng>,
+    ponder: Option<Ponder>,
+}
+
+impl AISearch {
+    fn run(&mut self) -> Result<SearchStats, String> {
+        if let Some((Ok(stats), rng)) = self.ponder.take().and_then(|ponder| ponder.handle.join().ok()) {
+            // The ponder drew from a copy of the seat's stream; carry on from where it left off.
+            self.rng = rng;
+            return Ok(stats);
+        }
+        choose_ai_move(&self.board, &self.config, &mut self.caches, self.rng.as_mut()).map(|(stats, _)| stats)
+    }
 }
 
 impl GameManager {
@@ -66,102 +365,1110 @@ impl GameManager {
         GameManager {
             board: None,
             config: None,
+            ai_caches: AICaches::default(),
+            generation: 0,
+            move_history: Vec::new(),
+            ai_rngs: HashMap::new(),
+            last_start: None,
+            move_observer: None,
+            ponder_enabled: false,
+            ponder: None,
+            ai_thinking: false,
+            snapshots: VecDeque::new(),
+        }
+    }
+
+    // Builder-style registration for library users; replaces any previously registered observer.
+    pub fn with_move_observer(mut self, observer: MoveObserver) -> Self {
+        self.move_observer = Some(observer);
+        self
+    }
+
+    pub fn set_move_observer(&mut self, observer: Option<MoveObserver>) {
+        self.move_observer = observer;
+    }
+
+    // Starts a new game for `config` as the next generation. If the frontend fires start_game twice,
+    // the second call hands back the game the first one just created instead of silently replacing it.
+    fn start_game(&mut self, config: GameConfigData, logger: Arc<dyn MoveLogger>) -> Result<GameStateData, String> {
+        if let (Some(started), Some(current_config), Some(board)) = (self.last_start, self.config.as_ref(), self.board.as_ref()) {
+            if started.elapsed() < Duration::from_millis(START_GAME_DEBOUNCE_MS) && *current_config == config && board.total_moves == 0 {
+                return Ok(convert_board_to_state_data(board, self.generation));
+            }
+        }
+
+        validate_config(&config)?;
+
+        let board = board_from_config(&config).with_logger(logger);
+        self.generation += 1;
+        self.last_start = Some(Instant::now());
+        let game_state_dto = convert_board_to_state_data(&board, self.generation);
+        self.board = Some(board);
+        self.ai_rngs = seeded_ai_rngs(&config);
+        self.config = Some(config);
+        self.move_history.clear();
+        self.reset_snapshots();
+        // Cached search results from the previous game may not apply to the new board.
+        self.ai_caches.clear();
+        Ok(game_state_dto)
+    }
+
+    // Rejects a command that names a game other than the current one.
+    fn check_generation(&self, generation: Option<u64>) -> Result<(), String> {
+        if generation.is_some_and(|g| g != self.generation) {
+            return Err("Stale game: a new game has been started".to_string());
+        }
+        Ok(())
+    }
+
+    // Applies a real move to the current board and notifies the observer if the move was legal.
+    pub fn apply_move(&mut self, row: usize, col: usize) -> Result<Vec<Frame>, String> {
+        self.apply_move_with_outcome(row, col).map(|outcome| outcome.history)
+    }
+
+    pub fn apply_moves(&mut self, moves: &[(usize, usize)]) -> Result<(), String> {
+        for (index, &(row, col)) in moves.iter().enumerate() {
+            self.apply_move(row, col).map_err(|e| format!("Move {} ({}, {}) is illegal: {}", index, row, col, e))?;
+        }
+        Ok(())
+    }
+
+    pub fn apply_move_with_outcome(&mut self, row: usize, col: usize) -> Result<MoveOutcome, String> {
+        self.ensure_not_thinking()?;
+        let board = self.board.as_mut().ok_or("Game not initialized")?;
+        let mover = board.current_turn;
+        let outcome = board.make_move_with_outcome(row, col).map_err(|e| e.to_string())?;
+        self.move_history.push((row, col));
+
+        if let Some(observer) = self.move_observer.as_mut() {
+            observer(mover, (row, col), board);
+        }
+        self.snapshots.push_back(board.snapshot());
+        if self.snapshots.len() > SNAPSHOT_LIMIT {
+            self.snapshots.pop_front();
+        }
+        self.start_pondering();
+        Ok(outcome)
+    }
+
+    // With pondering on and an alpha-beta AI to move, starts its search in the background. Any earlier
+    // ponder is dropped; its thread finishes on its own time limit and the result is discarded. The
+    // search gets fresh caches, since the game's stay with the manager.
+    fn start_pondering(&mut self) {
+        self.ponder = None;
+        if !self.ponder_enabled { return; }
+        let (Some(board), Some(config)) = (&self.board, &self.config) else { return };
+        if board.is_over() { return; }
+        let is_alphabeta_ai = config.player_config(board.current_turn).is_some_and(|player| {
+            player.player_type == "AI"
+                && player.resolved_ai_config().is_some_and(|ai_conf| parse_strategy(&ai_conf.strategy) == AIStrategy::AlphaBeta)
+        });
+        if !is_alphabeta_ai { return; }
+
+        let (board, config) = (board.clone(), config.clone());
+        let mut rng = self.ai_rngs.get(&board.current_turn).cloned();
+        let hash = board.zobrist_hash();
+        let handle = thread::spawn(move || {
+            let result = choose_ai_move(&board, &config, &mut AICaches::default(), rng.as_mut()).map(|(stats, _)| stats);
+            (result, rng)
+        });
+        self.ponder = Some(Ponder { hash, generation: self.generation, handle });
+    }
+
+    // The background search, if it is for exactly the current position; any other ponder is dropped.
+    fn take_matching_ponder(&mut self) -> Option<Ponder> {
+        let ponder = self.ponder.take()?;
+        let board = self.board.as_ref()?;
+        (ponder.hash == board.zobrist_hash() && ponder.generation == self.generation).then_some(ponder)
+    }
+
+    fn ensure_not_thinking(&self) -> Result<(), String> {
+        if self.ai_thinking {
+            return Err("The AI is still thinking".to_string());
+        }
+        Ok(())
+    }
+
+    // Copies the position and lends out the caches, the mover's RNG stream and any matching ponder so
+    // the search can run with the lock released. Until `finish_ai_search`, moves are refused.
+    fn begin_ai_search(&mut self) -> Result<AISearch, String> {
+        self.ensure_not_thinking()?;
+        let board = self.board.clone().ok_or("Game not initialized")?;
+        if board.is_over() {
+            return Err("The game is already over.".to_string());
+        }
+        let config = self.config.clone().ok_or("Game config missing")?;
+        let ponder = self.take_matching_ponder();
+        let rng = self.ai_rngs.remove(&board.current_turn);
+        self.ai_thinking = true;
+        Ok(AISearch { board, config, generation: self.generation, caches: std::mem::take(&mut self.ai_caches), rng, ponder })
+    }
+
+    // Takes back what `begin_ai_search` lent out. Returns false if the game was restarted or replaced
+    // during the search, in which case the old game's caches and RNG stream are thrown away.
+    fn finish_ai_search(&mut self, search: AISearch) -> bool {
+        self.ai_thinking = false;
+        let unchanged = search.generation == self.generation
+            && self.board.as_ref().is_some_and(|board| board.zobrist_hash() == search.board.zobrist_hash());
+        if unchanged {
+            self.ai_caches = search.caches;
+            if let Some(rng) = search.rng {
+                self.ai_rngs.insert(search.board.current_turn, rng);
+            }
+        }
+        unchanged
+    }
+
+    // Whether the side to move's AI, having found `stats`, would rather concede than play on.
+    fn wants_to_resign(&self, stats: &SearchStats) -> bool {
+        let (Some(board), Some(config)) = (&self.board, &self.config) else { return false };
+        board.player_count == 2
+            && config.player_config(board.current_turn)
+                .and_then(|player| player.resolved_ai_config())
+                .and_then(|ai_conf| ai_conf.resign_threshold)
+                .is_some_and(|threshold| ai::should_resign(stats, threshold))
+    }
+
+    fn resign(&mut self) -> Result<(), String> {
+        self.ensure_not_thinking()?;
+        let board = self.board.as_mut().ok_or("Game not initialized")?;
+        board.resign()?;
+        self.ponder = None;
+        Ok(())
+    }
+
+    // Takes back the last move applied through the manager.
+    pub fn undo_move(&mut self) -> Result<(), String> {
+        self.ensure_not_thinking()?;
+        let board = self.board.as_mut().ok_or("Game not initialized")?;
+        board.undo_move()?;
+        self.move_history.pop();
+        self.snapshots.pop_back();
+        if self.snapshots.is_empty() {
+            self.reset_snapshots();
         }
+        Ok(())
+    }
+
+    // Starts the snapshot buffer over from the current position, e.g. for a new or loaded game.
+    fn reset_snapshots(&mut self) {
+        self.snapshots.clear();
+        if let Some(board) = &self.board {
+            self.snapshots.push_back(board.snapshot());
+        }
+    }
+
+    // The position when `total_moves` was `index`, if it is still buffered.
+    pub fn snapshot(&self, index: usize) -> Option<&Board> {
+        let first = self.snapshots.front()?.total_moves as usize;
+        self.snapshots.get(index.checked_sub(first)?)
     }
 }
 
 // Helper function to convert a single Board state to a DTO
-fn convert_board_to_state_data(board: &Board) -> GameStateData {
-    let board_data = board.cells.iter().map(|row| {
-        row.iter().map(|cell| {
+fn convert_board_to_state_data(board: &Board, generation: u64) -> GameStateData {
+    let board_data = board.cells.iter().enumerate().map(|(r, row)| {
+        row.iter().enumerate().map(|(c, cell)| {
             let (player, orbs) = match cell.state {
                 game::CellState::Empty => (None, 0),
                 game::CellState::Occupied { player, orbs } => (Some(format!("{:?}", player)), orbs),
             };
-            CellData { player, orbs, critical_mass: cell.critical_mass }
+            CellData {
+                player, orbs, critical_mass: cell.critical_mass,
+                contested: board.is_contested(r, c),
+                capacity_fraction: orbs as f64 / cell.critical_mass.max(1) as f64,
+                will_explode_next: cell.explodes_on_next_orb(),
+                hidden: false,
+            }
         }).collect()
     }).collect();
     
-    let (game_status, winner) = match board.game_state {
-        game::GameState::Ongoing => ("ongoing".to_string(), None),
-        game::GameState::Won { winner } => ( "finished".to_string(), Some(format!("{:?}", winner)) )
+    let game_status = match board.game_state {
+        game::GameState::Ongoing => "ongoing",
+        game::GameState::Won { .. } => "finished",
+        game::GameState::Draw => "draw",
     };
     
     GameStateData {
         board: board_data,
         current_player: format!("{:?}", board.current_turn),
-        game_status,
-        winner,
+        game_status: game_status.to_string(),
+        winner: board.winner().map(|winner| format!("{:?}", winner)),
         red_orbs: board.orb_counts.get(&Player::Red).cloned().unwrap_or(0),
         blue_orbs: board.orb_counts.get(&Player::Blue).cloned().unwrap_or(0),
+        green_orbs: board.orb_counts.get(&Player::Green).cloned().unwrap_or(0),
+        yellow_orbs: board.orb_counts.get(&Player::Yellow).cloned().unwrap_or(0),
         total_moves: board.total_moves,
+        generation,
+        exploded: Vec::new(),
+    }
+}
+
+// `convert_board_to_state_data` for what `viewer` can see under fog of war: hidden cells are sent empty
+// and flagged, and orb totals only count visible orbs.
+fn convert_board_for_viewer(board: &Board, generation: u64, viewer: Player) -> GameStateData {
+    let mut state = convert_board_to_state_data(&board.visible_to(viewer), generation);
+    for (r, row) in state.board.iter_mut().enumerate() {
+        for (c, cell) in row.iter_mut().enumerate() {
+            cell.hidden = !board.is_visible_to(viewer, r, c);
+        }
+    }
+    state
+}
+
+// Helper function to map the frontend's strategy name onto the AI enum.
+fn parse_strategy(name: &str) -> AIStrategy {
+    match name {
+        "Random" => AIStrategy::Random, "AlphaBeta" => AIStrategy::AlphaBeta,
+        "Berserk" => AIStrategy::Berserk, "Minimax" => AIStrategy::Minimax,
+        _ => AIStrategy::Random,
+    }
+}
+
+// Helper function to map the frontend's heuristic names onto the AI enum.
+fn parse_heuristics(names: &[String]) -> Vec<Heuristic> {
+    names.iter().map(|h| match h.as_str() {
+        "OrbDifference" => Heuristic::OrbDifference, "PeripheralControl" => Heuristic::PeripheralControl,
+        "TerritoryControl" => Heuristic::TerritoryControl, "ChainReactionPotential" => Heuristic::ChainReactionPotential,
+        "ConversionPotential" => Heuristic::ConversionPotential, "CascadePotential" => Heuristic::CascadePotential,
+        "SafeMobility" => Heuristic::SafeMobility, "Mobility" => Heuristic::Mobility, "Influence" => Heuristic::Influence,
+        "Vulnerability" => Heuristic::Vulnerability, "CenterControl" => Heuristic::CenterControl,
+        _ => Heuristic::OrbDifference,
+    }).collect()
+}
+
+fn eval_options_from_config(ai_conf: &AIConfigData) -> EvalOptions {
+    EvalOptions { zero_sum: ai_conf.zero_sum, weights: ai_conf.weights.clamped() }
+}
+
+fn ai_player_from_config(ai_conf: &AIConfigData) -> AIPlayerConfig {
+    AIPlayerConfig {
+        strategy: parse_strategy(&ai_conf.strategy),
+        heuristics: parse_heuristics(&ai_conf.heuristics),
+        eval_options: eval_options_from_config(ai_conf),
+        depth: ai_conf.depth,
+        time_limit_ms: ai_conf.time_limit_ms,
     }
 }
 
+// Keeps every `coalesce`-th frame and always the last one, so the final state is never dropped. A kept
+// frame also lists the explosions of the frames dropped before it, so none go unflashed.
+fn coalesce_frames(frames: Vec<Frame>, coalesce: u32) -> Vec<Frame> {
+    let step = coalesce.max(1) as usize;
+    let last = frames.len().saturating_sub(1);
+    let mut pending = Vec::new();
+    let mut kept = Vec::new();
+    for (i, mut frame) in frames.into_iter().enumerate() {
+        pending.append(&mut frame.exploded);
+        if (i + 1) % step == 0 || i == last {
+            frame.exploded = std::mem::take(&mut pending);
+            kept.push(frame);
+        }
+    }
+    kept
+}
+
+fn convert_frame_to_state_data(frame: &Frame, generation: u64) -> GameStateData {
+    GameStateData { exploded: frame.exploded.clone(), ..convert_board_to_state_data(&frame.board, generation) }
+}
+
+// Sub-seed streams derived from a game seed, one per randomized component.
+const SEED_STREAM_AI_MOVE: u64 = 1;
+const SEED_STREAM_ROLLOUT: u64 = 2;
+const SEED_STREAM_TIE_BREAK: u64 = 3;
+
+// Mixes the game seed, component stream and ply (splitmix64 finaliser) into an independent sub-seed,
+// so the same game seed and the same sequence of moves always reproduce the same random choices.
+fn derive_seed(game_seed: u64, stream: u64, ply: u32) -> u64 {
+    let mut z = game_seed ^ stream.wrapping_mul(0x9E37_79B9_7F4A_7C15) ^ (ply as u64).wrapping_mul(0xD1B5_4A32_D192_ED03);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+// A repeated start_game with the same config inside this window is treated as a double fire.
+const START_GAME_DEBOUNCE_MS: u64 = 500;
+
+const BOARD_PRESETS: [(&str, u32, u32); 3] = [("Classic", 6, 9), ("Small", 5, 5), ("Large", 10, 10)];
+
+// A search depth of 0 would never look at a single move.
+fn validate_ai_config(ai_conf: &AIConfigData) -> Result<(), String> {
+    if ai_conf.depth == 0 {
+        return Err("AI search depth must be at least 1".to_string());
+    }
+    Ok(())
+}
+
+// The live game's latest position is kept here, for `recover_from_log`.
+const LOG_FILENAME: &str = "../game_log.txt";
+// And every move it has played, for `replay_from_log`.
+const MOVES_FILENAME: &str = "../game_moves.txt";
+
+fn file_logger() -> Arc<FileMoveLogger> {
+    Arc::new(FileMoveLogger::new(LOG_FILENAME, MOVES_FILENAME))
+}
+
+// A fresh board for `config`, before any move has been played. Nothing is logged until a logger is attached.
+fn board_from_config(config: &GameConfigData) -> Board {
+    let mut board = Board::new(config.width, config.height, Player::Red, config.player_count(), CriticalMassRule::NeighbourCount, config.topology());
+    board.no_moves_rule = match config.no_moves_rule.as_deref() {
+        Some("Draw") => NoMovesRule::Draw,
+        _ => NoMovesRule::Loss,
+    };
+    board.opening_rule = config.opening_rule();
+    board.forced_capture = ForcedCapture(config.forced_capture);
+    board
+}
+
+fn validate_config(config: &GameConfigData) -> Result<(), String> {
+    Board::validate_dimensions(config.width, config.height)?;
+    let player_count = config.player_count();
+    if !(2..=Player::ALL.len()).contains(&player_count) {
+        return Err(format!("Player count must be between 2 and {}, got {}", Player::ALL.len(), player_count));
+    }
+    if let Some(missing) = Player::ALL[..player_count].iter().find(|&&p| config.player_config(p).is_none()) {
+        return Err(format!("Missing config for {:?}", missing));
+    }
+    for player in Player::ALL[..player_count].iter().filter_map(|&p| config.player_config(p)) {
+        if let Some(difficulty) = player.difficulty.as_deref().filter(|d| Difficulty::parse(d).is_none()) {
+            return Err(format!("Unknown difficulty for {}: {}", player.name, difficulty));
+        }
+        if let Some(ai_conf) = &player.ai_config {
+            validate_ai_config(ai_conf).map_err(|e| format!("{}: {}", player.name, e))?;
+        }
+    }
+    Ok(())
+}
+
 // --- Tauri Commands ---
 
 #[tauri::command]
 fn start_game(config: GameConfigData, state: State<Mutex<GameManager>>) -> Result<GameStateData, String> {
+    let logger = file_logger();
     let mut manager = state.lock().unwrap();
-    let log_filename = "../game_log.txt".to_string(); 
-    let board = Board::new(config.width, config.height, Player::Red, log_filename);
-    let game_state_dto = convert_board_to_state_data(&board);
-    manager.board = Some(board);
-    manager.config = Some(config);
-    Ok(game_state_dto)
+    let game_state = manager.start_game(config, logger.clone())?;
+    // A repeated start keeps the current game, which hasn't moved yet, so clearing is safe either way.
+    logger.clear_moves();
+    Ok(game_state)
+}
+
+// Plays a move and streams its animation: each intermediate board is emitted as a "chain-step"
+// event and only the final state is returned, so large chain reactions don't travel as one huge
+// payload. `coalesce` keeps only every Nth explosion frame (plus the final state) for faster replays.
+#[tauri::command]
+fn make_move(row: usize, col: usize, coalesce: Option<u32>, generation: Option<u64>, state: State<Mutex<GameManager>>, app: AppHandle) -> Result<GameStateData, MoveErrorData> {
+    let history = make_move_frames(row, col, coalesce, generation, &state)?;
+
+    for (sequence, frame) in history.iter().enumerate() {
+        // The move has already been played, so a frame the window misses is only lost animation.
+        if let Err(e) = app.emit("chain-step", ChainStepEvent { sequence, state: frame.clone() }) {
+            eprintln!("Warning: Failed to emit chain step {}: {}", sequence, e);
+        }
+    }
+    history.last().cloned().ok_or_else(|| "Move produced no board states".to_string().into())
 }
 
+// `make_move` returning every frame at once instead of emitting events, for replay tooling.
 #[tauri::command]
-// FIX: This command now returns the entire animation history to the frontend.
-fn make_move(row: usize, col: usize, state: State<Mutex<GameManager>>, _app: AppHandle) -> Result<Vec<GameStateData>, String> {
+fn make_move_with_history(row: usize, col: usize, coalesce: Option<u32>, generation: Option<u64>, state: State<Mutex<GameManager>>) -> Result<Vec<GameStateData>, String> {
+    make_move_frames(row, col, coalesce, generation, &state).map_err(|e| e.message)
+}
+
+fn make_move_frames(row: usize, col: usize, coalesce: Option<u32>, generation: Option<u64>, state: &State<Mutex<GameManager>>) -> Result<Vec<GameStateData>, MoveErrorData> {
     let mut manager = state.lock().unwrap();
-    let board = manager.board.as_mut().ok_or("Game not initialized")?;
-    
-    let history_of_boards = board.make_move_and_get_history(row, col).map_err(|e| e.to_string())?;
+    manager.check_generation(generation)?;
+    let board = manager.board.as_ref().ok_or_else(|| "Game not initialized".to_string())?;
+    board.check_move(row, col).map_err(|e| MoveErrorData::from_move_error(e, board, row, col))?;
+    let frames = manager.apply_move(row, col)?;
 
-    // Convert the Vec<Board> into a Vec<GameStateData> for the frontend.
-    let history_for_frontend = history_of_boards
-        .into_iter()
-        .map(|b| convert_board_to_state_data(&b))
+    // Convert the Vec<Frame> into a Vec<GameStateData> for the frontend.
+    let history_for_frontend = coalesce_frames(frames, coalesce.unwrap_or(1))
+        .iter()
+        .map(|frame| convert_frame_to_state_data(frame, manager.generation))
         .collect();
     
     Ok(history_for_frontend)
 }
 
+// `make_move` plus the move's explosion count and per-player orb swing, so overlays don't have to
+// diff the frames themselves.
+#[tauri::command]
+fn make_move_with_outcome(row: usize, col: usize, coalesce: Option<u32>, generation: Option<u64>, state: State<Mutex<GameManager>>) -> Result<MoveOutcomeData, String> {
+    let mut manager = state.lock().unwrap();
+    manager.check_generation(generation)?;
+    let outcome = manager.apply_move_with_outcome(row, col)?;
+
+    Ok(MoveOutcomeData {
+        history: coalesce_frames(outcome.history, coalesce.unwrap_or(1))
+            .iter()
+            .map(|frame| convert_frame_to_state_data(frame, manager.generation))
+            .collect(),
+        explosion_steps: outcome.explosion_steps,
+        red_delta: outcome.red_delta,
+        blue_delta: outcome.blue_delta,
+        green_delta: outcome.green_delta,
+        yellow_delta: outcome.yellow_delta,
+    })
+}
 
 #[tauri::command]
-fn get_ai_move_command(state: State<Mutex<GameManager>>) -> Result<(usize, usize), String> {
-    let manager = state.lock().unwrap();
+fn undo(state: State<Mutex<GameManager>>) -> Result<GameStateData, String> {
+    let mut manager = state.lock().unwrap();
+    manager.undo_move()?;
     let board = manager.board.as_ref().ok_or("Game not initialized")?;
-    let config = manager.config.as_ref().ok_or("Game config missing")?;
+    Ok(convert_board_to_state_data(board, manager.generation))
+}
+
+// Plays `moves` in order on the current board. Stops at the first illegal move and reports its index;
+// the moves before it stay applied.
+#[tauri::command]
+fn apply_moves(moves: Vec<(usize, usize)>, state: State<Mutex<GameManager>>) -> Result<GameStateData, String> {
+    let mut manager = state.lock().unwrap();
+    manager.apply_moves(&moves)?;
+    let board = manager.board.as_ref().ok_or("Game not initialized")?;
+    Ok(convert_board_to_state_data(board, manager.generation))
+}
+
 
-    let ai_player_color = board.current_turn;
-    let ai_player_config = if ai_player_color == Player::Red { &config.red_player } else { &config.blue_player };
+// A fresh random stream for every seat whose AI config carries its own seed.
+fn seeded_ai_rngs(config: &GameConfigData) -> HashMap<Player, StdRng> {
+    Player::ALL[..config.player_count()].iter()
+        .filter_map(|&player| {
+            let seed = config.player_config(player)?.ai_config.as_ref()?.seed?;
+            Some((player, StdRng::seed_from_u64(seed)))
+        })
+        .collect()
+}
+
+// The configured AI's move for the side to move with its search stats, along with the AI config that
+// produced it. Alpha-beta searches reuse the game's transposition table across turns. A player
+// configured by difficulty alone gets that difficulty's preset. Random choices come from `rng` when
+// the seat has its own stream, else from the game seed, else from the thread RNG.
+fn choose_ai_move<'a>(board: &Board, config: &'a GameConfigData, caches: &mut AICaches, rng: Option<&mut StdRng>) -> Result<(SearchStats, Cow<'a, AIConfigData>), String> {
+    let ai_player_config = config.player_config(board.current_turn).ok_or("No config for the current player")?;
     
     if ai_player_config.player_type == "AI" {
-        if let Some(ai_conf) = &ai_player_config.ai_config {
-            let strategy = match ai_conf.strategy.as_str() {
-                "Random" => AIStrategy::Random, "AlphaBeta" => AIStrategy::AlphaBeta,
-                _ => AIStrategy::Random,
-            };
-            let heuristics: Vec<Heuristic> = ai_conf.heuristics.iter().map(|h| match h.as_str() {
-                "OrbDifference" => Heuristic::OrbDifference, "PeripheralControl" => Heuristic::PeripheralControl,
-                "TerritoryControl" => Heuristic::TerritoryControl, "ChainReactionPotential" => Heuristic::ChainReactionPotential,
-                "ConversionPotential" => Heuristic::ConversionPotential, "CascadePotential" => Heuristic::CascadePotential,
-                "SafeMobility" => Heuristic::SafeMobility, _ => Heuristic::OrbDifference,
-            }).collect();
+        if let Some(ai_conf) = ai_player_config.resolved_ai_config() {
+            let strategy = parse_strategy(&ai_conf.strategy);
+            let heuristics = parse_heuristics(&ai_conf.heuristics);
             
-            return Ok(get_ai_move(board, strategy, &heuristics, ai_conf.depth, ai_conf.time_limit_ms));
+            let options = eval_options_from_config(&ai_conf);
+            let chosen = if strategy == AIStrategy::AlphaBeta {
+                let tie_break = parse_tie_break(&ai_conf, board, config, rng);
+                ai::opening_book_move(board)
+                    .map(|book_move| SearchStats { chosen: book_move, score: f64::NAN, depth_reached: 0, elapsed_ms: 0, nodes: 0 })
+                    .or_else(|| ai::alphabeta_move(board, &heuristics, options, ai_conf.depth, ai_conf.time_limit_ms, tie_break, &mut caches.transpositions))
+            } else if let Some(rng) = rng {
+                ai::get_ai_move_with_rng(board, strategy, &heuristics, options, ai_conf.depth, ai_conf.time_limit_ms, rng)
+            } else if let Some(game_seed) = config.game_seed {
+                let mut rng = StdRng::seed_from_u64(derive_seed(game_seed, SEED_STREAM_AI_MOVE, board.total_moves));
+                ai::get_ai_move_with_rng(board, strategy, &heuristics, options, ai_conf.depth, ai_conf.time_limit_ms, &mut rng)
+            } else {
+                get_ai_move(board, strategy, &heuristics, options, ai_conf.depth, ai_conf.time_limit_ms)
+            };
+            return chosen.map(|stats| (stats, ai_conf)).ok_or_else(|| "No legal moves available".to_string());
         }
     }
     Err("Current player is not an AI".to_string())
 }
 
+// The tie-break `ai_conf` names. A random one is seeded like random moves are: from the seat's own
+// stream, else the game seed, else the thread RNG.
+fn parse_tie_break(ai_conf: &AIConfigData, board: &Board, config: &GameConfigData, rng: Option<&mut StdRng>) -> TieBreak {
+    match ai_conf.tie_break.as_deref() {
+        Some("NearCorner") => TieBreak::NearCorner,
+        Some("Shallow") => TieBreak::Shallow,
+        Some("Random") => TieBreak::Random(match (rng, config.game_seed) {
+            (Some(rng), _) => rng.gen(),
+            (None, Some(game_seed)) => derive_seed(game_seed, SEED_STREAM_TIE_BREAK, board.total_moves),
+            (None, None) => rand::random(),
+        }),
+        _ => TieBreak::First,
+    }
+}
+
+// Lets the AI start thinking about its reply as soon as the move before it is applied, so
+// `get_ai_move_command` and `play_ai_turn` can answer at once. Only alpha-beta AIs ponder.
 #[tauri::command]
-fn get_current_state(state: State<Mutex<GameManager>>) -> Result<GameStateData, String> {
+fn set_pondering(enabled: bool, state: State<Mutex<GameManager>>) {
+    let mut manager = state.lock().unwrap();
+    manager.ponder_enabled = enabled;
+    if !enabled {
+        manager.ponder = None;
+    }
+}
+
+// Runs the side to move's AI search on the blocking thread pool with the lock released, so reads like
+// `get_current_state` stay responsive while the AI thinks; moves made in the meantime are rejected.
+// Returns the lock, retaken, along with the search's result for the position it was started on.
+async fn search_off_lock<'a>(state: &'a State<'_, Mutex<GameManager>>) -> Result<(MutexGuard<'a, GameManager>, SearchStats), String> {
+    let mut search = state.lock().unwrap().begin_ai_search()?;
+    let joined = tauri::async_runtime::spawn_blocking(move || {
+        let result = search.run();
+        (search, result)
+    }).await;
+
+    let mut manager = state.lock().unwrap();
+    let (search, result) = match joined {
+        Ok(joined) => joined,
+        Err(e) => {
+            manager.ai_thinking = false;
+            return Err(format!("AI search failed: {}", e));
+        }
+    };
+    if !manager.finish_ai_search(search) {
+        return Err("The game changed while the AI was thinking".to_string());
+    }
+    Ok((manager, result?))
+}
+
+// None means the AI resigned instead of moving; the game is then over and won by its opponent.
+#[tauri::command]
+async fn get_ai_move_command(state: State<'_, Mutex<GameManager>>) -> Result<Option<(usize, usize)>, String> {
+    let (mut manager, stats) = search_off_lock(&state).await?;
+    println!("AI chose {:?}: depth {} in {} ms, {} nodes", stats.chosen, stats.depth_reached, stats.elapsed_ms, stats.nodes);
+    if manager.wants_to_resign(&stats) {
+        println!("AI resigns with a best score of {}", stats.score);
+        manager.resign()?;
+        return Ok(None);
+    }
+    Ok(Some(stats.chosen))
+}
+
+// Search settings for a hint when the side to move has no alpha-beta config of its own (a Human, or an AI
+// on another strategy).
+const HINT_DEPTH: u32 = 3;
+const HINT_TIME_LIMIT_MS: u64 = 3000;
+const HINT_HEURISTICS: [Heuristic; 3] = [Heuristic::OrbDifference, Heuristic::CascadePotential, Heuristic::SafeMobility];
+
+// The move the side to move's search would pick, its score from that player's point of view, and the
+// number of positions visited. `strategy` ("AlphaBeta" or "Minimax") overrides the configured one, so
+// the two node counts can be compared on the same position and depth. Each hint starts with an empty
+// transposition table so the counts don't depend on earlier turns.
+#[tauri::command]
+fn get_hint(strategy: Option<String>, state: State<Mutex<GameManager>>) -> Result<(usize, usize, f64, u64), String> {
     let manager = state.lock().unwrap();
     let board = manager.board.as_ref().ok_or("Game not initialized")?;
-    Ok(convert_board_to_state_data(board))
+    if board.is_over() {
+        return Err("The game is already over.".to_string());
+    }
+
+    let player_config = manager.config.as_ref().and_then(|config| config.player_config(board.current_turn));
+    let ai_conf = player_config
+        .and_then(|player| player.resolved_ai_config())
+        .filter(|ai_conf| matches!(parse_strategy(&ai_conf.strategy), AIStrategy::AlphaBeta | AIStrategy::Minimax));
+    let (heuristics, options, depth, time_limit_ms) = match ai_conf.as_deref() {
+        Some(ai_conf) => (parse_heuristics(&ai_conf.heuristics), eval_options_from_config(ai_conf), ai_conf.depth, ai_conf.time_limit_ms),
+        None => (HINT_HEURISTICS.to_vec(), EvalOptions::default(), HINT_DEPTH, HINT_TIME_LIMIT_MS),
+    };
+    let strategy = strategy.as_deref()
+        .or(ai_conf.as_deref().map(|ai_conf| ai_conf.strategy.as_str()))
+        .map_or(AIStrategy::AlphaBeta, parse_strategy);
+    let params = SearchParams { max_depth: depth, time_limit_ms, pruning: strategy != AIStrategy::Minimax, tie_break: TieBreak::First };
+
+    let result = ai::search_move(board, &heuristics, options, params, &mut ai::TranspositionTable::default())
+        .ok_or("No legal moves available")?;
+    Ok((result.chosen.0, result.chosen.1, result.score, result.nodes_visited))
+}
+
+// Lets the side to move's configured AI play its turn and returns the animation frames, so the
+// frontend can loop AI-vs-AI games. Errors for a Human player so the UI knows to wait for input. Like
+// `get_ai_move_command`, the search runs with the lock released.
+#[tauri::command]
+async fn play_ai_turn(coalesce: Option<u32>, state: State<'_, Mutex<GameManager>>) -> Result<Vec<GameStateData>, String> {
+    let (mut manager, stats) = search_off_lock(&state).await?;
+    // A resignation ends the game without a move, so the finished position is the only frame.
+    if manager.wants_to_resign(&stats) {
+        manager.resign()?;
+        let board = manager.board.as_ref().ok_or("Game not initialized")?;
+        return Ok(vec![convert_board_to_state_data(board, manager.generation)]);
+    }
+    let frames = manager.apply_move(stats.chosen.0, stats.chosen.1)?;
+
+    Ok(coalesce_frames(frames, coalesce.unwrap_or(1))
+        .iter()
+        .map(|frame| convert_frame_to_state_data(frame, manager.generation))
+        .collect())
+}
+
+// The AI's move plus the static evaluation of the position after it, always from Red's point of
+// view so consecutive values can drive one evaluation bar. The search runs with the lock released.
+#[tauri::command]
+async fn get_ai_move_and_eval(state: State<'_, Mutex<GameManager>>) -> Result<((usize, usize), f64), String> {
+    let (manager, stats) = search_off_lock(&state).await?;
+    let board = manager.board.as_ref().ok_or("Game not initialized")?;
+    let ai_conf = manager.config.as_ref()
+        .and_then(|config| config.player_config(board.current_turn))
+        .and_then(|player| player.resolved_ai_config())
+        .ok_or("No AI config for the current player")?;
+    let a_move = stats.chosen;
+    let heuristics = parse_heuristics(&ai_conf.heuristics);
+    let options = eval_options_from_config(&ai_conf);
+    let evaluation = ai::evaluate_after_move(board, a_move, &heuristics, options, Player::Red).map_err(|e| e.to_string())?;
+    Ok((a_move, evaluation))
+}
+
+#[tauri::command]
+fn heuristic_sensitivity(heuristics: Vec<String>, state: State<Mutex<GameManager>>) -> Result<Vec<(String, f64)>, String> {
+    let manager = state.lock().unwrap();
+    let board = manager.board.as_ref().ok_or("Game not initialized")?;
+    if board.is_over() {
+        return Err("The game has already been won.".to_string());
+    }
+
+    let heuristics = parse_heuristics(&heuristics);
+    let sensitivity = ai::heuristic_sensitivity(board, &heuristics, EvalOptions::default())?;
+    Ok(sensitivity.into_iter().map(|(h, change)| (format!("{:?}", h), change)).collect())
+}
+
+// The finished game's summary for the stats screen, or None while it is still in progress.
+#[tauri::command]
+fn get_game_result(state: State<Mutex<GameManager>>) -> Result<Option<GameResult>, String> {
+    let manager = state.lock().unwrap();
+    let board = manager.board.as_ref().ok_or("Game not initialized")?;
+    Ok(board.game_result())
+}
+
+// The live position's static evaluation for the side to move, e.g. to graph it over a game.
+#[tauri::command]
+fn evaluate_position(heuristics: Vec<String>, state: State<Mutex<GameManager>>) -> Result<f64, String> {
+    let manager = state.lock().unwrap();
+    let board = manager.board.as_ref().ok_or("Game not initialized")?;
+
+    let heuristics = parse_heuristics(&heuristics);
+    Ok(ai::score_board(board, &heuristics, board.current_turn))
+}
+
+// Per-heuristic (name, raw, weighted) values for the side to move, then a "NearWin" row for the
+// elimination bonus and a "Total" row whose weighted value is the evaluation itself. `weights`
+// defaults to the built-in ones, so tuned values can be compared against them.
+#[tauri::command]
+fn breakdown(heuristics: Vec<String>, weights: Option<HeuristicWeights>, state: State<Mutex<GameManager>>) -> Result<Vec<(String, f64, f64)>, String> {
+    let manager = state.lock().unwrap();
+    let board = manager.board.as_ref().ok_or("Game not initialized")?;
+    Ok(breakdown_rows(board, &parse_heuristics(&heuristics), weights.unwrap_or_default()))
+}
+
+fn breakdown_rows(board: &Board, heuristics: &[Heuristic], weights: HeuristicWeights) -> Vec<(String, f64, f64)> {
+    let options = EvalOptions { weights: weights.clamped(), ..EvalOptions::default() };
+    let breakdown = ai::evaluate_breakdown(board, heuristics, options, board.current_turn);
+    let mut rows: Vec<(String, f64, f64)> = breakdown.heuristics.iter()
+        .map(|(h, raw, weighted)| (format!("{:?}", h), *raw, *weighted))
+        .collect();
+    rows.push(("NearWin".to_string(), breakdown.near_win.0, breakdown.near_win.1));
+
+    let total_raw = rows.iter().map(|(_, raw, _)| raw).sum();
+    rows.push(("Total".to_string(), total_raw, breakdown.total()));
+    rows
+}
+
+// Without an explicit seed the rollout is derived from the game seed, or fully random if there is none.
+#[tauri::command]
+fn random_rollout(seed: Option<u64>, state: State<Mutex<GameManager>>) -> Result<GameStateData, String> {
+    let manager = state.lock().unwrap();
+    let board = manager.board.as_ref().ok_or("Game not initialized")?;
+    let game_seed = manager.config.as_ref().and_then(|config| config.game_seed);
+    let seed = seed
+        .or_else(|| game_seed.map(|game_seed| derive_seed(game_seed, SEED_STREAM_ROLLOUT, board.total_moves)))
+        .unwrap_or_else(rand::random);
+    let final_board = ai::random_rollout(board, seed);
+    Ok(convert_board_to_state_data(&final_board, manager.generation))
+}
+
+#[tauri::command]
+fn run_self_play(config: SelfPlayConfigData) -> Result<Vec<SelfPlayGameData>, String> {
+    Board::validate_dimensions(config.width, config.height)?;
+    validate_ai_config(&config.red_ai)?;
+    validate_ai_config(&config.blue_ai)?;
+    let red = ai_player_from_config(&config.red_ai);
+    let blue = ai_player_from_config(&config.blue_ai);
+    let params = SelfPlayParams {
+        width: config.width,
+        height: config.height,
+        games: config.games,
+        seed: config.seed,
+        random_opening_plies: config.random_opening_plies,
+    };
+
+    let records = selfplay::run_self_play(&red, &blue, &params);
+    Ok(records.iter().map(|record| SelfPlayGameData {
+        winner: record.winner.map(|w| format!("{:?}", w)),
+        total_moves: record.moves.len() as u32,
+        fingerprint: record.fingerprint(),
+    }).collect())
+}
+
+// Weight tuning plays on the classic board with a short random opening so games differ.
+const OPTIMIZER_BOARD: (u32, u32) = (6, 9);
+const OPTIMIZER_GAMES_PER_EVAL: u32 = 10;
+const OPTIMIZER_OPENING_PLIES: u32 = 4;
+
+// The move number after which the game swung most toward its winner, for a "key moment" highlight.
+#[tauri::command]
+fn find_turning_point(state: State<Mutex<GameManager>>) -> Result<Option<u32>, String> {
+    let manager = state.lock().unwrap();
+    let board = manager.board.as_ref().ok_or("Game not initialized")?;
+    let config = manager.config.as_ref().ok_or("Game config missing")?;
+    let Some(winner) = board.winner() else {
+        return Err("The game has no winner yet".to_string());
+    };
+    if manager.move_history.len() != board.total_moves as usize {
+        return Err("The full move history of this game is not available".to_string());
+    }
+
+    let start = board_from_config(config);
+    ai::find_turning_point(&start, &manager.move_history, winner).map_err(|e| e.to_string())
+}
+
+// Every move of the current game next to the best move the search found there, for a review screen.
+// `depth` defaults to the analysis depth used elsewhere; a deeper review takes noticeably longer, so
+// it runs on the blocking thread pool with the lock released.
+#[tauri::command]
+async fn analyze_game(heuristics: Vec<String>, depth: Option<u32>, state: State<'_, Mutex<GameManager>>) -> Result<Vec<ai::MoveAnalysis>, String> {
+    let (start, moves) = {
+        let manager = state.lock().unwrap();
+        let board = manager.board.as_ref().ok_or("Game not initialized")?;
+        let config = manager.config.as_ref().ok_or("Game config missing")?;
+        if manager.move_history.len() != board.total_moves as usize {
+            return Err("The full move history of this game is not available".to_string());
+        }
+
+        // Tag each move with its mover by replaying the game.
+        let start = board_from_config(config);
+        let mut replay = start.clone();
+        let mut moves = Vec::with_capacity(manager.move_history.len());
+        for &(row, col) in &manager.move_history {
+            moves.push((replay.current_turn, row, col));
+            replay.make_move_for_simulation(row, col, None).map_err(|e| e.to_string())?;
+        }
+        (start, moves)
+    };
+
+    let heuristics = parse_heuristics(&heuristics);
+    tauri::async_runtime::spawn_blocking(move || ai::analyze_game(&start, &moves, &heuristics, depth.unwrap_or(ai::ANALYSIS_DEPTH)))
+        .await
+        .map_err(|e| format!("Analysis failed: {}", e))?
+}
+
+#[tauri::command]
+fn optimize_weights(base: AIConfigData, opponent: AIConfigData, iterations: u32, seed: u64) -> Result<HeuristicWeights, String> {
+    if iterations > selfplay::OPTIMIZER_MAX_ITERATIONS {
+        return Err(format!("At most {} iterations are allowed", selfplay::OPTIMIZER_MAX_ITERATIONS));
+    }
+    let params = SelfPlayParams {
+        width: OPTIMIZER_BOARD.0,
+        height: OPTIMIZER_BOARD.1,
+        games: OPTIMIZER_GAMES_PER_EVAL,
+        seed,
+        random_opening_plies: OPTIMIZER_OPENING_PLIES,
+    };
+    Ok(selfplay::optimize_weights(&ai_player_from_config(&base), &ai_player_from_config(&opponent), &params, iterations))
+}
+
+#[tauri::command]
+fn solve_plan(state: State<Mutex<GameManager>>) -> Result<Vec<(usize, usize)>, String> {
+    let mut manager = state.lock().unwrap();
+    let GameManager { board, ai_caches, .. } = &mut *manager;
+    let board = board.as_ref().ok_or("Game not initialized")?;
+    Ok(ai::solve_plan(board, ai_caches)?)
+}
+
+#[tauri::command]
+fn clear_ai_caches(state: State<Mutex<GameManager>>) -> Result<(), String> {
+    let mut manager = state.lock().unwrap();
+    manager.ai_caches.clear();
+    Ok(())
+}
+
+#[tauri::command]
+fn estimate_remaining_moves(state: State<Mutex<GameManager>>) -> Result<u32, String> {
+    let manager = state.lock().unwrap();
+    let board = manager.board.as_ref().ok_or("Game not initialized")?;
+    Ok(ai::estimate_remaining_moves(board))
+}
+
+#[tauri::command]
+fn tempo_score(state: State<Mutex<GameManager>>) -> Result<f64, String> {
+    let manager = state.lock().unwrap();
+    let board = manager.board.as_ref().ok_or("Game not initialized")?;
+    Ok(ai::tempo_score(board))
+}
+
+#[tauri::command]
+fn win_requirement(state: State<Mutex<GameManager>>) -> Result<HashMap<String, String>, String> {
+    let manager = state.lock().unwrap();
+    let board = manager.board.as_ref().ok_or("Game not initialized")?;
+    Ok(ai::win_requirements(board).into_iter().map(|(player, text)| (format!("{:?}", player), text)).collect())
+}
+
+#[tauri::command]
+fn classify_moves(state: State<Mutex<GameManager>>) -> Result<MoveClassification, String> {
+    let manager = state.lock().unwrap();
+    let board = manager.board.as_ref().ok_or("Game not initialized")?;
+    if board.is_over() {
+        return Err("The game has already been won.".to_string());
+    }
+    Ok(ai::classify_moves(board))
+}
+
+#[tauri::command]
+fn sealed_territory(state: State<Mutex<GameManager>>) -> Result<HashMap<String, usize>, String> {
+    let manager = state.lock().unwrap();
+    let board = manager.board.as_ref().ok_or("Game not initialized")?;
+    Ok(board.players().map(|player| (format!("{:?}", player), board.sealed_territory(player))).collect())
+}
+
+#[tauri::command]
+fn volatility(state: State<Mutex<GameManager>>) -> Result<f64, String> {
+    let manager = state.lock().unwrap();
+    let board = manager.board.as_ref().ok_or("Game not initialized")?;
+    Ok(board.volatility())
+}
+
+// Named (name, width, height) sizes for the quick-start menu.
+#[tauri::command]
+fn get_board_presets() -> Vec<(String, u32, u32)> {
+    BOARD_PRESETS.iter()
+        .filter(|(_, width, height)| Board::validate_dimensions(*width, *height).is_ok())
+        .map(|(name, width, height)| (name.to_string(), *width, *height))
+        .collect()
+}
+
+#[tauri::command]
+fn orb_histogram(state: State<Mutex<GameManager>>) -> Result<HashMap<String, Vec<u32>>, String> {
+    let manager = state.lock().unwrap();
+    let board = manager.board.as_ref().ok_or("Game not initialized")?;
+    Ok(board.orb_histogram().into_iter().map(|(player, counts)| (format!("{:?}", player), counts)).collect())
+}
+
+// Every position of a logged game ("Red 3 4" move lines), for scrubbing through it. The path defaults
+// to the live game's move log and the board size to the current game's. The live game is left untouched.
+#[tauri::command]
+fn replay_from_log(path: Option<String>, width: Option<u32>, height: Option<u32>, state: State<Mutex<GameManager>>) -> Result<Vec<GameStateData>, String> {
+    let path = path.unwrap_or_else(|| MOVES_FILENAME.to_string());
+    let manager = state.lock().unwrap();
+    let config = manager.config.as_ref();
+    let width = width.or(config.map(|config| config.width)).ok_or("Board width missing")?;
+    let height = height.or(config.map(|config| config.height)).ok_or("Board height missing")?;
+    Board::validate_dimensions(width, height)?;
+
+    let content = std::fs::read_to_string(&path).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+    let snapshots = Board::replay_log(&content, width, height)?;
+    Ok(snapshots.iter().map(|board| convert_board_to_state_data(board, manager.generation)).collect())
+}
+
+// The settled position after move `index`, numbered like `total_moves` (0 is the start of a fresh
+// game), so a spectator view can scrub through the game. Only the latest SNAPSHOT_LIMIT are kept.
+#[tauri::command]
+fn get_snapshot(index: usize, state: State<Mutex<GameManager>>) -> Result<GameStateData, String> {
+    let manager = state.lock().unwrap();
+    let board = manager.snapshot(index).ok_or_else(|| format!("No snapshot for move {}", index))?;
+    Ok(convert_board_to_state_data(board, manager.generation))
+}
+
+// Returned as hex because JavaScript numbers can't hold a full u64.
+#[tauri::command]
+fn get_canonical_hash(state: State<Mutex<GameManager>>) -> Result<String, String> {
+    let manager = state.lock().unwrap();
+    let board = manager.board.as_ref().ok_or("Game not initialized")?;
+    Ok(format!("{:016x}", board.canonical_hash()))
+}
+
+// In a fog-of-war game, passing `viewer` ("Red", "Blue", ...) returns only what that player can see.
+// Without a viewer, or with fog of war off, the whole board is returned.
+#[tauri::command]
+fn get_current_state(viewer: Option<String>, state: State<Mutex<GameManager>>) -> Result<GameStateData, String> {
+    let manager = state.lock().unwrap();
+    let board = manager.board.as_ref().ok_or("Game not initialized")?;
+    let fog_of_war = manager.config.as_ref().is_some_and(|config| config.fog_of_war);
+    match viewer.filter(|_| fog_of_war) {
+        Some(name) => {
+            let viewer = board::parse_player_name(&name).ok_or_else(|| format!("Unknown player: {}", name))?;
+            Ok(convert_board_for_viewer(board, manager.generation, viewer))
+        }
+        None => Ok(convert_board_to_state_data(board, manager.generation)),
+    }
+}
+
+// The cells the side to move may play, so the UI can grey out the rest. Empty before a game starts.
+#[tauri::command]
+fn get_legal_moves(state: State<Mutex<GameManager>>) -> Result<Vec<(usize, usize)>, String> {
+    let manager = state.lock().unwrap();
+    let Some(board) = manager.board.as_ref() else { return Ok(Vec::new()) };
+    if board.is_over() {
+        return Err("The game is already over.".to_string());
+    }
+    Ok(board.get_all_valid_moves())
+}
+
+const SAVE_FILENAME: &str = "../game_save.json";
+
+// What a save file holds: the position and the config it is being played under, so loading brings
+// back the players, rules and AI seeds along with the board.
+#[derive(Serialize, Deserialize)]
+struct SaveFile {
+    config: GameConfigData,
+    // Kept as raw JSON so loading goes through `Board::from_json`'s checks.
+    board: serde_json::Value,
+}
+
+fn save_file_json(manager: &GameManager) -> Result<String, String> {
+    let board = manager.board.as_ref().ok_or("Game not initialized")?;
+    let config = manager.config.clone().ok_or("Game config missing")?;
+    let board = serde_json::to_value(board).map_err(|e| format!("Failed to save the board: {}", e))?;
+    serde_json::to_string(&SaveFile { config, board }).map_err(|e| format!("Failed to save the game: {}", e))
+}
+
+// The config and position in a save file. Saves written before the config was stored hold only the
+// board, so they come back with no config.
+fn read_save(json: &str) -> Result<(Option<GameConfigData>, Board), String> {
+    let value: serde_json::Value = serde_json::from_str(json).map_err(|e| e.to_string())?;
+    if value.get("config").is_none() {
+        return Ok((None, Board::from_json(json).map_err(|e| e.to_string())?));
+    }
+    let save: SaveFile = serde_json::from_value(value).map_err(|e| e.to_string())?;
+    let board = Board::from_json(&save.board.to_string()).map_err(|e| e.to_string())?;
+    Ok((Some(save.config), board))
+}
+
+// Whether `board` could have been played under `config`: same size, seats and topology.
+fn check_board_matches_config(board: &Board, config: &GameConfigData) -> Result<(), String> {
+    if (board.width, board.height) != (config.width, config.height) {
+        return Err(format!("the board is {}x{} but the game is {}x{}", board.width, board.height, config.width, config.height));
+    }
+    if board.player_count != config.player_count() {
+        return Err(format!("the board has {} players but the game has {}", board.player_count, config.player_count()));
+    }
+    if board.topology != config.topology() {
+        return Err(format!("the board is a {:?} but the game is a {:?}", board.topology, config.topology()));
+    }
+    Ok(())
+}
+
+// Makes a save's game the live one. A save without a config can only be loaded into a game it
+// fits, whose config it then keeps.
+fn install_save(manager: &mut GameManager, json: &str) -> Result<GameStateData, String> {
+    let (config, board) = read_save(json)?;
+    let config = match config {
+        Some(config) => {
+            validate_config(&config)?;
+            config
+        }
+        None => manager.config.clone().ok_or("This save has no game config; start a matching game first")?,
+    };
+    check_board_matches_config(&board, &config).map_err(|e| format!("The save does not fit its game: {}", e))?;
+    Ok(install_loaded_board(manager, config, board))
+}
+
+// Writes the exact current position, including the side to move, and the game's config so both
+// survive a restart.
+#[tauri::command]
+fn save_game(state: State<Mutex<GameManager>>) -> Result<(), String> {
+    let json = save_file_json(&state.lock().unwrap())?;
+    std::fs::write(SAVE_FILENAME, json).map_err(|e| format!("Failed to write save file: {}", e))
+}
+
+#[tauri::command]
+fn load_game(state: State<Mutex<GameManager>>) -> Result<GameStateData, String> {
+    let json = std::fs::read_to_string(SAVE_FILENAME).map_err(|e| format!("Failed to read save file: {}", e))?;
+    install_save(&mut state.lock().unwrap(), &json).map_err(|e| format!("Could not load save file: {}", e))
+}
+
+// Makes a loaded position and its config the live game, as a new generation with no move history.
+// Seeded AIs restart their random streams from their seeds.
+fn install_loaded_board(manager: &mut GameManager, config: GameConfigData, mut board: Board) -> GameStateData {
+    // A position written mid-cascade would leave over-critical cells behind; finish them off. This
+    // also recounts the orbs and rehashes cells copied in from a log.
+    board.resolve_pending();
+    board.set_logger(file_logger());
+    manager.generation += 1;
+    let game_state_dto = convert_board_to_state_data(&board, manager.generation);
+    manager.board = Some(board);
+    manager.ai_rngs = seeded_ai_rngs(&config);
+    manager.config = Some(config);
+    // The save only holds the position, not the moves that led to it.
+    manager.move_history.clear();
+    manager.reset_snapshots();
+    manager.ai_caches.clear();
+    game_state_dto
+}
+
+const SLOT_NAME_MAX_LEN: usize = 64;
+
+// One entry of the "continue a game" list: enough to recognise a save without loading it.
+#[derive(Debug, Clone, Serialize)]
+pub struct GameSlotData {
+    pub name: String,
+    pub width: u32,
+    pub height: u32,
+    pub total_moves: u32,
+    pub current_player: String,
+}
+
+// Slot names become file names, so only letters, digits, spaces, '-' and '_' are allowed. That
+// rules out separators and "..", keeping every slot inside the saves directory.
+fn validate_slot_name(name: &str) -> Result<(), String> {
+    if name.trim().is_empty() || name.len() > SLOT_NAME_MAX_LEN {
+        return Err(format!("Slot names must be 1 to {} characters long", SLOT_NAME_MAX_LEN));
+    }
+    if !name.chars().all(|c| c.is_ascii_alphanumeric() || c == ' ' || c == '-' || c == '_') {
+        return Err(format!("Invalid slot name {:?}: use letters, digits, spaces, '-' or '_'", name));
+    }
+    Ok(())
+}
+
+fn slots_dir(app: &AppHandle) -> Result<std::path::PathBuf, String> {
+    let dir = app.path().app_data_dir().map_err(|e| format!("No app data directory: {}", e))?;
+    Ok(dir.join("saves"))
+}
+
+fn slot_path(app: &AppHandle, name: &str) -> Result<std::path::PathBuf, String> {
+    validate_slot_name(name)?;
+    Ok(slots_dir(app)?.join(format!("{}.json", name)))
+}
+
+// Like `save_game`, but under a name of the player's choosing in the app data directory, overwriting
+// any earlier save with the same name.
+#[tauri::command]
+fn save_game_slot(name: String, state: State<Mutex<GameManager>>, app: AppHandle) -> Result<(), String> {
+    let path = slot_path(&app, &name)?;
+    let json = save_file_json(&state.lock().unwrap())?;
+    std::fs::create_dir_all(slots_dir(&app)?).map_err(|e| format!("Failed to create saves directory: {}", e))?;
+    std::fs::write(path, json).map_err(|e| format!("Failed to write slot {}: {}", name, e))
+}
+
+#[tauri::command]
+fn load_game_slot(name: String, state: State<Mutex<GameManager>>, app: AppHandle) -> Result<GameStateData, String> {
+    let json = std::fs::read_to_string(slot_path(&app, &name)?).map_err(|e| format!("Failed to read slot {}: {}", name, e))?;
+    install_save(&mut state.lock().unwrap(), &json).map_err(|e| format!("Could not load slot {}: {}", name, e))
+}
+
+// Every readable slot, sorted by name. Files that aren't valid saves are skipped.
+#[tauri::command]
+fn list_game_slots(app: AppHandle) -> Result<Vec<GameSlotData>, String> {
+    let dir = slots_dir(&app)?;
+    let entries = match std::fs::read_dir(&dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(format!("Failed to read saves directory: {}", e)),
+    };
+
+    let mut slots: Vec<GameSlotData> = entries.filter_map(|entry| {
+        let path = entry.ok()?.path();
+        if path.extension()? != "json" { return None; }
+        let name = path.file_stem()?.to_str()?.to_string();
+        validate_slot_name(&name).ok()?;
+        let (_, board) = read_save(&std::fs::read_to_string(&path).ok()?).ok()?;
+        Some(GameSlotData {
+            name,
+            width: board.width,
+            height: board.height,
+            total_moves: board.total_moves,
+            current_player: format!("{:?}", board.current_turn),
+        })
+    }).collect();
+    slots.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(slots)
 }
 
 #[tauri::command]
@@ -170,97 +1477,58 @@ fn recover_from_log(state: State<Mutex<GameManager>>) -> Result<GameStateData, S
     use std::path::Path;
     
     let mut manager = state.lock().unwrap();
-    let config = manager.config.as_ref().ok_or("Game config missing")?;
+    manager.ensure_not_thinking()?;
+    let config = manager.config.clone().ok_or("Game config missing")?;
     
     // Try to read the log file
-    let log_path = Path::new("../game_log.txt");
-    if !log_path.exists() {
-        let alt_path = Path::new("game_log.txt");
-        if !alt_path.exists() {
-            return Err("Log file not found".to_string());
-        }
-    }
-    
-    let log_content = fs::read_to_string(log_path.exists().then(|| log_path).unwrap_or(Path::new("game_log.txt")))
+    let log_path = [Path::new(LOG_FILENAME), Path::new("game_log.txt")].into_iter()
+        .find(|path| path.exists())
+        .ok_or("Log file not found")?;
+    let log_content = fs::read_to_string(log_path)
         .map_err(|e| format!("Failed to read log file: {}", e))?;
-    
+    let board = board_from_log(&log_content, &config)?;
+
+    // Like a loaded save, the recovered position starts a new generation with nothing carried over.
+    Ok(install_loaded_board(&mut manager, config, board))
+}
+
+// The last position written to a move log, on a board built with `config`'s settings. The cells are
+// copied in as they are; `install_loaded_board` settles and rehashes them.
+fn board_from_log(log_content: &str, config: &GameConfigData) -> Result<Board, String> {
     // Parse the last board state from the log
     let lines: Vec<&str> = log_content.lines().collect();
     if lines.len() < 2 {
         return Err("Log file is empty or corrupted".to_string());
     }
     
-    // Find the last "AI Move:" section
-    let mut board_lines = Vec::new();
-    let mut found_ai_move = false;
-    
-    for line in lines.iter().rev() {
-        if line.starts_with("AI Move:") {
-            found_ai_move = true;
-            break;
-        }
-        if found_ai_move {
-            board_lines.insert(0, *line);
-        }
-    }
-    
-    if !found_ai_move {
-        // Get the last section of lines that look like board state
-        let mut start_idx = lines.len().saturating_sub(config.height as usize);
-        for i in (0..lines.len()).rev() {
-            if lines[i].contains("AI Move:") {
-                start_idx = i + 1;
-                break;
-            }
-        }
-        board_lines = lines[start_idx..].to_vec();
-    }
+    // The board follows the last "Human Move:"/"AI Move:" header; without one, assume the log ends with it.
+    let header_idx = lines.iter().rposition(|line| line.starts_with("Human Move:") || line.starts_with("AI Move:"));
+    let board_lines = match header_idx {
+        Some(i) => lines[i + 1..].to_vec(),
+        None => lines[lines.len().saturating_sub(config.height as usize)..].to_vec(),
+    };
     
     if board_lines.is_empty() || board_lines.len() != config.height as usize {
         return Err("Could not parse board state from log".to_string());
     }
     
-    // Create a new board and parse the state
-    let mut board = Board::new(config.width, config.height, Player::Red, "../game_log.txt".to_string());
-    
-    for (row, line) in board_lines.iter().enumerate() {
-        let cells: Vec<&str> = line.split_whitespace().collect();
-        if cells.len() != config.width as usize {
-            return Err(format!("Invalid board row in log: {}", line))?;
-        }
-        
-        for (col, cell_str) in cells.iter().enumerate() {
-            if *cell_str == "0" {
-                // Empty cell
-                continue;
-            }
-            
-            let orbs = cell_str.chars()
-                .take_while(|c| c.is_ascii_digit())
-                .collect::<String>()
-                .parse::<u32>()
-                .map_err(|_| format!("Invalid orb count: {}", cell_str))?;
-            
-            let player = if cell_str.contains('R') {
-                Player::Red
-            } else if cell_str.contains('B') {
-                Player::Blue
-            } else {
-                return Err(format!("Invalid player in cell: {}", cell_str))?;
-            };
-            
-            // Update cell state using the correct structure
-            board.cells[row][col].state = CellState::Occupied { player, orbs };
+    // Parse the grid, then carry its cells over to a board built with the game's own settings.
+    let parsed = Board::from_ascii(&board_lines).map_err(|e| format!("Could not parse board state from log: {}", e))?;
+    if parsed.width != config.width {
+        return Err(format!("Invalid board row in log: expected {} cells, found {}", config.width, parsed.width));
+    }
+    let mut board = board_from_config(config);
+    for (row, cells) in parsed.cells.iter().enumerate() {
+        for (col, cell) in cells.iter().enumerate() {
+            board.cells[row][col].state = cell.state;
         }
     }
     
-    // Update the current player (this is a guess - you might want to track this in the log too)
-    board.current_turn = Player::Red; // Default, could be improved
-    
-    // Update the manager state
-    manager.board = Some(board.clone());
-    
-    Ok(convert_board_to_state_data(&board))
+    // Logs written before the header recorded the side to move fall back to Red.
+    let (current_turn, total_moves) = header_idx.and_then(|i| parse_log_header(lines[i])).unwrap_or((Player::Red, 0));
+    board.current_turn = current_turn;
+    board.total_moves = total_moves;
+    Ok(board)
 }
 
 pub fn run() {
@@ -271,8 +1539,400 @@ pub fn run() {
             make_move,
             get_ai_move_command,
             get_current_state,
-            recover_from_log
+            recover_from_log,
+            heuristic_sensitivity,
+            random_rollout,
+            run_self_play,
+            get_canonical_hash,
+            solve_plan,
+            estimate_remaining_moves,
+            clear_ai_caches,
+            breakdown,
+            orb_histogram,
+            tempo_score,
+            win_requirement,
+            classify_moves,
+            sealed_territory,
+            volatility,
+            get_board_presets,
+            get_ai_move_and_eval,
+            apply_moves,
+            optimize_weights,
+            find_turning_point,
+            undo,
+            get_hint,
+            play_ai_turn,
+            save_game,
+            load_game,
+            make_move_with_outcome,
+            make_move_with_history,
+            evaluate_position,
+            replay_from_log,
+            get_snapshot,
+            get_game_result,
+            get_legal_moves,
+            set_pondering,
+            analyze_game,
+            save_game_slot,
+            load_game_slot,
+            list_game_slots
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+    use board::NoopLogger;
+
+    // Red is human and Blue a random AI seeded with `seed`.
+    fn test_config(width: u32, height: u32, seed: u64) -> GameConfigData {
+        let ai_config = AIConfigData { seed: Some(seed), ..ai_config_for(Difficulty::Easy) };
+        GameConfigData {
+            width,
+            height,
+            red_player: PlayerConfigData { player_type: "Human".to_string(), name: "Red".to_string(), ai_config: None, difficulty: None },
+            blue_player: PlayerConfigData { player_type: "AI".to_string(), name: "Blue".to_string(), ai_config: Some(ai_config), difficulty: None },
+            no_moves_rule: None,
+            game_seed: None,
+            player_count: None,
+            green_player: None,
+            yellow_player: None,
+            topology: None,
+            opening_rule: None,
+            forced_capture: false,
+            fog_of_war: false,
+        }
+    }
+
+    // A manager with `config`'s game started, without start_game's log file.
+    fn started(config: GameConfigData) -> GameManager {
+        let mut manager = GameManager::new();
+        manager.board = Some(board_from_config(&config));
+        manager.ai_rngs = seeded_ai_rngs(&config);
+        manager.config = Some(config);
+        manager
+    }
+
+    #[test]
+    fn loading_a_save_restores_its_config_and_ai_seeds() {
+        let saved_config = test_config(5, 5, 7);
+        let mut saved = started(saved_config.clone());
+        saved.apply_move(2, 2).unwrap();
+        let json = save_file_json(&saved).unwrap();
+
+        let mut manager = started(test_config(6, 9, 1));
+        manager.ai_rngs.clear();
+        install_save(&mut manager, &json).unwrap();
+
+        assert_eq!(manager.config.as_ref(), Some(&saved_config));
+        let board = manager.board.as_ref().unwrap();
+        assert_eq!(board.zobrist_hash(), saved.board.as_ref().unwrap().zobrist_hash());
+        assert_eq!(board.current_turn, Player::Blue);
+        assert!(manager.ai_rngs.contains_key(&Player::Blue));
+        assert_eq!(manager.generation, 1);
+    }
+
+    #[test]
+    fn board_only_saves_must_fit_the_current_game() {
+        let legacy = board_from_config(&test_config(5, 5, 7)).to_json();
+
+        assert!(install_save(&mut GameManager::new(), &legacy).is_err());
+        let mut mismatched = started(test_config(6, 9, 1));
+        assert!(install_save(&mut mismatched, &legacy).is_err());
+        assert_eq!(mismatched.board.as_ref().unwrap().width, 6);
+
+        let config = test_config(5, 5, 3);
+        let mut matching = started(config.clone());
+        install_save(&mut matching, &legacy).unwrap();
+        assert_eq!(matching.config, Some(config));
+    }
+
+    #[test]
+    fn moves_wait_for_an_ai_search_off_the_lock() {
+        let mut manager = started(test_config(5, 5, 7));
+        manager.apply_move(0, 0).unwrap();
+
+        let mut search = manager.begin_ai_search().unwrap();
+        assert!(!manager.ai_rngs.contains_key(&Player::Blue), "the seat's stream is lent to the search");
+        assert!(manager.apply_move(4, 4).is_err());
+        assert!(manager.begin_ai_search().is_err());
+
+        let stats = search.run().unwrap();
+        assert!(manager.finish_ai_search(search));
+        assert!(manager.ai_rngs.contains_key(&Player::Blue));
+        manager.apply_move(stats.chosen.0, stats.chosen.1).unwrap();
+    }
+
+    #[test]
+    fn a_pondered_move_advances_the_seat_stream_like_a_search() {
+        let mut config = test_config(5, 5, 7);
+        config.blue_player.ai_config = Some(AIConfigData {
+            strategy: "AlphaBeta".to_string(),
+            depth: 1,
+            heuristics: vec!["CenterControl".to_string()],
+            tie_break: Some("Random".to_string()),
+            ..config.blue_player.ai_config.clone().unwrap()
+        });
+        let mut searched = started(config.clone());
+        let mut pondered = started(config);
+        pondered.ponder_enabled = true;
+
+        let mut next_draws = Vec::new();
+        for manager in [&mut searched, &mut pondered] {
+            manager.apply_move(0, 0).unwrap();
+            let mut search = manager.begin_ai_search().unwrap();
+            let stats = search.run().unwrap();
+            assert!(manager.finish_ai_search(search));
+            let next_draw: u64 = manager.ai_rngs.get_mut(&Player::Blue).unwrap().gen();
+            next_draws.push((stats.chosen, next_draw));
+        }
+        assert!(pondered.ponder.is_none());
+        assert_eq!(next_draws[0], next_draws[1]);
+    }
+
+    #[test]
+    fn state_data_flags_the_front_line_cells() {
+        let board = Board::from_ascii(&["1R 1R 0", "0 1B 0", "1R 0 1B"]).unwrap();
+        let data = convert_board_to_state_data(&board, 0);
+        let contested: Vec<bool> = data.board.iter().flatten().map(|cell| cell.contested).collect();
+        assert_eq!(contested, [false, true, false, false, true, false, false, false, false]);
+    }
+
+    #[test]
+    fn the_move_observer_sees_each_legal_move_once() {
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let recorder = seen.clone();
+        let mut manager = started(test_config(5, 5, 7)).with_move_observer(Box::new(move |player, (row, col), board: &Board| {
+            recorder.lock().unwrap().push((player, (row, col), board.total_moves, board.cells[row][col].state));
+        }));
+
+        manager.apply_move(0, 0).unwrap();
+        assert!(manager.apply_move(0, 0).is_err(), "Blue can't play on Red's cell");
+        manager.apply_move(4, 4).unwrap();
+
+        assert_eq!(*seen.lock().unwrap(), [
+            (Player::Red, (0, 0), 1, game::CellState::Occupied { player: Player::Red, orbs: 1 }),
+            (Player::Blue, (4, 4), 2, game::CellState::Occupied { player: Player::Blue, orbs: 1 }),
+        ]);
+    }
+
+    #[test]
+    fn coalescing_keeps_every_nth_frame_and_the_final_state() {
+        // Red's primed corner sets off the whole row of primed cells, one explosion per frame.
+        let mut board = Board::from_ascii(&["1R 2R 2R 2R 2R 1R", "0 0 0 0 0 1B"]).unwrap();
+        let frames = board.make_move_with_outcome(0, 0).unwrap().history;
+        assert!(frames.len() >= 6, "only {} frames", frames.len());
+        let explosions = |frames: &[Frame]| frames.iter().map(|frame| frame.exploded.len()).sum::<usize>();
+
+        assert_eq!(coalesce_frames(frames.clone(), 1).len(), frames.len());
+        for n in [2, 3, frames.len() as u32 + 1] {
+            let kept = coalesce_frames(frames.clone(), n);
+            assert_eq!(kept.len(), frames.len().div_ceil(n as usize), "coalescing by {}", n);
+            assert_eq!(kept.last().unwrap().board.zobrist_hash(), frames.last().unwrap().board.zobrist_hash());
+            assert_eq!(explosions(&kept), explosions(&frames));
+        }
+    }
+
+    #[test]
+    fn breakdown_rows_add_up_to_the_evaluation() {
+        let mut board = board_from_config(&test_config(6, 9, 7));
+        board.apply_moves(&[(0, 0), (8, 5), (0, 1), (4, 3), (1, 0), (4, 4)]).unwrap();
+        let weights = HeuristicWeights { orb_difference: 2.0, ..HeuristicWeights::default() };
+        let rows = breakdown_rows(&board, &Heuristic::ALL, weights);
+
+        let (total, parts) = rows.split_last().unwrap();
+        assert_eq!(total.0, "Total");
+        assert_eq!(parts.len(), Heuristic::ALL.len() + 1);
+        let summed: f64 = parts.iter().map(|(_, _, weighted)| weighted).sum();
+        assert!((summed - total.2).abs() < 1e-9, "rows add up to {}, total is {}", summed, total.2);
+    }
+
+    #[test]
+    fn a_loaded_mid_cascade_position_is_settled() {
+        let mut board = board_from_config(&test_config(5, 5, 7));
+        board.apply_moves(&[(0, 0), (4, 4), (0, 1), (4, 3)]).unwrap();
+        // Red's corner is left holding three orbs, one past its critical mass.
+        board.cells[0][0].state = game::CellState::Occupied { player: Player::Red, orbs: 3 };
+        let mut manager = started(test_config(5, 5, 7));
+
+        let state = install_save(&mut manager, &board.to_json()).unwrap();
+
+        let loaded = manager.board.as_ref().unwrap();
+        assert!(loaded.is_resolved());
+        assert!(state.board.iter().flatten().all(|cell| cell.orbs < cell.critical_mass));
+        assert_eq!(state.red_orbs + state.blue_orbs, 6);
+    }
+
+    #[test]
+    fn a_repeated_start_keeps_the_game_and_a_new_one_retires_stale_calls() {
+        let mut manager = GameManager::new();
+        let first = manager.start_game(test_config(5, 5, 7), Arc::new(NoopLogger)).unwrap();
+        let repeated = manager.start_game(test_config(5, 5, 7), Arc::new(NoopLogger)).unwrap();
+        assert_eq!(repeated.generation, first.generation);
+        manager.check_generation(Some(first.generation)).unwrap();
+
+        let second = manager.start_game(test_config(6, 9, 7), Arc::new(NoopLogger)).unwrap();
+        assert_eq!(second.generation, first.generation + 1);
+        assert!(manager.check_generation(Some(first.generation)).is_err());
+        manager.check_generation(Some(second.generation)).unwrap();
+        manager.check_generation(None).unwrap();
+        assert_eq!(manager.board.as_ref().unwrap().width, 6);
+    }
+
+    #[test]
+    fn every_board_preset_makes_a_valid_game() {
+        let presets = get_board_presets();
+        assert_eq!(presets.len(), BOARD_PRESETS.len());
+        for (name, width, height) in presets {
+            validate_config(&test_config(width, height, 7)).unwrap_or_else(|e| panic!("{}: {}", name, e));
+        }
+    }
+
+    #[test]
+    fn the_game_seed_alone_makes_a_match_repeatable() {
+        // Red's "human" always takes the first legal cell; Blue is a random AI without its own seed.
+        let play = |game_seed| {
+            let mut config = test_config(5, 5, 0);
+            config.blue_player.ai_config.as_mut().unwrap().seed = None;
+            config.game_seed = Some(game_seed);
+            let mut manager = started(config);
+            for _ in 0..12 {
+                let human = manager.board.as_ref().unwrap().get_all_valid_moves()[0];
+                manager.apply_move(human.0, human.1).unwrap();
+                if manager.board.as_ref().unwrap().is_over() { break; }
+                let mut search = manager.begin_ai_search().unwrap();
+                let stats = search.run().unwrap();
+                assert!(manager.finish_ai_search(search));
+                manager.apply_move(stats.chosen.0, stats.chosen.1).unwrap();
+                if manager.board.as_ref().unwrap().is_over() { break; }
+            }
+            manager.move_history
+        };
+
+        assert_eq!(play(42), play(42));
+        assert_ne!(play(42), play(43));
+    }
+
+    #[test]
+    fn state_data_reports_fill_levels_and_primed_cells() {
+        let board = Board::from_ascii(&["0 1R 1R", "2B 3B 0", "0 0 0"]).unwrap();
+        let data = convert_board_to_state_data(&board, 0);
+        let cells: Vec<(f64, bool)> = data.board[..2].iter().flatten().map(|cell| (cell.capacity_fraction, cell.will_explode_next)).collect();
+        assert_eq!(cells, [
+            (0.0, false), (1.0 / 3.0, false), (0.5, true),
+            (2.0 / 3.0, true), (0.75, true), (0.0, false),
+        ]);
+    }
+
+    #[test]
+    fn batch_moves_stop_at_the_first_illegal_one() {
+        let mut manager = started(test_config(5, 5, 7));
+        manager.apply_moves(&[(0, 0), (4, 4), (0, 1)]).unwrap();
+        assert_eq!(manager.move_history, [(0, 0), (4, 4), (0, 1)]);
+
+        // Red can't take Blue's corner, so (2, 2) is never reached.
+        let error = manager.apply_moves(&[(3, 3), (4, 4), (2, 2)]).unwrap_err();
+        assert!(error.starts_with("Move 1 (4, 4) is illegal"), "{}", error);
+        assert_eq!(manager.move_history, [(0, 0), (4, 4), (0, 1), (3, 3)]);
+    }
+
+    #[test]
+    fn move_errors_reach_the_ui_with_their_own_kind() {
+        let mut board = board_from_config(&test_config(5, 5, 7));
+        board.apply_moves(&[(0, 0), (4, 4)]).unwrap();
+
+        let off_board = MoveErrorData::from_move_error(MoveError::OutOfBounds { row: 5, col: 0 }, &board, 5, 0);
+        assert_eq!((off_board.kind.as_str(), off_board.width, off_board.height), ("OutOfBounds", Some(5), Some(5)));
+        let taken = MoveErrorData::from_move_error(MoveError::OpponentCell, &board, 4, 4);
+        assert_eq!((taken.kind.as_str(), taken.owner.as_deref(), taken.orbs), ("OpponentCell", Some("Blue"), Some(1)));
+
+        let kinds: HashSet<String> = [MoveError::GameOver, MoveError::OpeningRule(OpeningRule::OwnHalf), MoveError::MustCapture, MoveError::SimulationTimeout]
+            .into_iter()
+            .map(|error| MoveErrorData::from_move_error(error, &board, 0, 0).kind)
+            .chain([off_board.kind, taken.kind])
+            .collect();
+        assert_eq!(kinds.len(), 6);
+    }
+
+    #[test]
+    fn recovery_restores_the_side_to_move_from_the_log() {
+        let config = test_config(5, 5, 7);
+        let mut board = board_from_config(&config);
+        board.apply_moves(&[(0, 0), (4, 4), (2, 2)]).unwrap();
+        let path = std::env::temp_dir().join("chain_reaction_recovery_test_log.txt");
+        let moves_path = std::env::temp_dir().join("chain_reaction_recovery_test_moves.txt");
+        FileMoveLogger::new(path.to_string_lossy(), moves_path.to_string_lossy()).save_position(&board);
+
+        let log = std::fs::read_to_string(&path).unwrap();
+        let recovered = board_from_log(&log, &config).unwrap();
+        assert_eq!(recovered.current_turn, Player::Blue);
+        assert_eq!(recovered.total_moves, 3);
+        assert_eq!(recovered.to_log_text(), board.to_log_text());
+
+        // A log from before the header recorded the turn falls back to Red.
+        let headerless: String = log.lines().skip(1).map(|line| format!("{}\n", line)).collect();
+        assert_eq!(board_from_log(&format!("Red 0 0\n{}", headerless), &config).unwrap().current_turn, Player::Red);
+    }
+
+    #[test]
+    fn a_seeded_random_ai_repeats_its_moves() {
+        // Red's "human" always takes the first legal cell; Blue is a random AI with its own seed and
+        // no game seed, so only Blue's stream decides its moves.
+        let ai_moves = |seed| {
+            let mut manager = started(test_config(5, 5, seed));
+            let mut moves = Vec::new();
+            for _ in 0..10 {
+                let human = manager.board.as_ref().unwrap().get_all_valid_moves()[0];
+                manager.apply_move(human.0, human.1).unwrap();
+                if manager.board.as_ref().unwrap().is_over() { break; }
+                let mut search = manager.begin_ai_search().unwrap();
+                let stats = search.run().unwrap();
+                assert!(manager.finish_ai_search(search));
+                manager.apply_move(stats.chosen.0, stats.chosen.1).unwrap();
+                moves.push(stats.chosen);
+                if manager.board.as_ref().unwrap().is_over() { break; }
+            }
+            moves
+        };
+
+        assert_eq!(ai_moves(7), ai_moves(7));
+        assert_ne!(ai_moves(7), ai_moves(8));
+    }
+
+    #[test]
+    fn invalid_configs_are_refused_before_a_game_starts() {
+        assert_eq!(validate_config(&test_config(1, 5, 0)), Ok(()));
+        assert!(validate_config(&test_config(0, 5, 0)).is_err());
+        assert!(validate_config(&test_config(5, 0, 0)).is_err());
+        assert!(validate_config(&test_config(board::MAX_BOARD_DIM + 1, 5, 0)).is_err());
+
+        let mut zero_depth = test_config(5, 5, 0);
+        zero_depth.blue_player.ai_config.as_mut().unwrap().depth = 0;
+        assert!(validate_config(&zero_depth).is_err_and(|e| e.contains("depth")));
+        assert!(GameManager::new().start_game(zero_depth, Arc::new(NoopLogger)).is_err());
+    }
+
+    #[test]
+    fn the_apps_own_move_log_replays() {
+        let dir = std::env::temp_dir();
+        let (path, moves_path) = (dir.join("chain_reaction_replay_test_log.txt"), dir.join("chain_reaction_replay_test_moves.txt"));
+        let logger = Arc::new(FileMoveLogger::new(path.to_string_lossy(), moves_path.to_string_lossy()));
+        logger.clear_moves();
+
+        let config = test_config(4, 4, 3);
+        let mut board = board_from_config(&config).with_logger(logger);
+        for (row, col) in [(0, 0), (3, 3), (0, 0), (3, 2), (1, 1)] {
+            board.make_move_with_outcome(row, col).unwrap();
+        }
+
+        // The position file is rewritten after every move, but the move list only grows.
+        let moves = std::fs::read_to_string(&moves_path).unwrap();
+        let snapshots = Board::replay_log(&moves, config.width, config.height).unwrap();
+        assert_eq!(snapshots.len(), 5);
+        assert_eq!(snapshots.last().unwrap().to_log_text(), board.to_log_text());
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), board.to_log_text());
+    }
+}
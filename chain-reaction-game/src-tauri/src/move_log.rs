@@ -0,0 +1,64 @@
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+
+use crate::board::{Board, MoveLogger};
+use crate::game::Player;
+
+// The desktop app's `MoveLogger`: appends each move as a "Red 3 4" line to `moves_path`, which
+// `Board::replay_log` reads back, and overwrites `path` with the settled position after every move so
+// `recover_from_log` can pick the game back up after a crash.
+pub struct FileMoveLogger {
+    path: String,
+    moves_path: String,
+}
+
+impl FileMoveLogger {
+    pub fn new(path: impl Into<String>, moves_path: impl Into<String>) -> Self {
+        FileMoveLogger { path: path.into(), moves_path: moves_path.into() }
+    }
+
+    // Empties the move list, for a new game; the position file is rewritten on its first move anyway.
+    pub fn clear_moves(&self) {
+        if let Err(e) = File::create(&self.moves_path) {
+            eprintln!("Warning: Could not clear move log {}: {}", self.moves_path, e);
+        }
+    }
+}
+
+impl MoveLogger for FileMoveLogger {
+    fn log_move(&self, player: Player, row: usize, col: usize) {
+        // Print current working directory for debugging
+        if let Ok(current_dir) = std::env::current_dir() {
+            println!("Current working directory: {:?}", current_dir);
+        }
+        println!("Attempting to write to log file: {}", self.moves_path);
+        
+        if let Ok(mut file) = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.moves_path) {
+            let move_str = format!("{:?} {} {}\n", player, row, col);
+            if let Err(e) = file.write_all(move_str.as_bytes()) {
+                eprintln!("Warning: Failed to write to log file: {}", e);
+            } else {
+                // Ensure the data is actually written to disk
+                if let Err(e) = file.flush() {
+                    eprintln!("Warning: Failed to flush log file: {}", e);
+                } else {
+                    println!("Successfully logged move: {:?} {} {} to file: {}", player, row, col, self.moves_path);
+                }
+            }
+        } else {
+            eprintln!("Warning: Could not open log file: {}", self.moves_path);
+        }
+    }
+
+    fn save_position(&self, board: &Board) {
+        let mut file = File::create(&self.path).expect("Could not open file");
+        file.write_all(board.to_log_text().as_bytes()).expect("Failed to write");
+    }
+
+    fn chain_reaction_cut_off(&self, explosions: usize) {
+        eprintln!("Warning: chain reaction stopped after {} explosions without settling", explosions);
+    }
+}